@@ -0,0 +1,57 @@
+//! Collects every inline `phoneme`, and `sub` tag out of a set of markup files, and prints
+//! a PLS lexicon ready for Polly's `PutLexicon`. Conflicting pronunciations are reported
+//! on stderr.
+//!
+//! ```text
+//! cargo run --example export_lexicon -- en-US scripts/*.txt > lexicon.pls
+//! ```
+
+use std::env;
+use std::fs;
+use std::process;
+
+use text_to_polly_ssml::lexicon::collect_lexicon;
+
+fn main() {
+  let mut args = env::args().skip(1);
+  let lang = match args.next() {
+    Some(lang) => lang,
+    None => {
+      eprintln!("Usage: export_lexicon <xml:lang> <markup files...>");
+      process::exit(2);
+    },
+  };
+
+  let mut files = Vec::new();
+  for path in args {
+    match fs::read_to_string(&path) {
+      Ok(contents) => files.push((path, contents)),
+      Err(error) => {
+        eprintln!("Failed to read {}: {}", path, error);
+        process::exit(1);
+      },
+    };
+  }
+  let documents = files.iter()
+    .map(|(path, contents)| (path.as_str(), contents.as_str()))
+    .collect::<Vec<(&str, &str)>>();
+
+  let collected = match collect_lexicon(&documents, Some(&lang)) {
+    Ok(collected) => collected,
+    Err(error) => {
+      eprintln!("Failed to collect the lexicon: {}", error);
+      process::exit(1);
+    },
+  };
+  for conflict in &collected.conflicts {
+    eprintln!("{}", conflict);
+  }
+
+  match collected.lexicon.to_pls() {
+    Ok(pls) => println!("{}", pls),
+    Err(error) => {
+      eprintln!("Failed to write the lexicon: {}", error);
+      process::exit(1);
+    },
+  };
+}
@@ -0,0 +1,278 @@
+//! Expands abbreviations, and spells out acronyms. Polly reads `e.g.` as "eg", and `SLA`
+//! as "slah", so a dictionary of them lets text be written the way people actually write
+//! it, while still being read the way it should be. Every match is wrapped in either a
+//! `sub` tag with the expanded text, or a `say-as` tag reading it as `characters`.
+//!
+//! Each language has a small built-in dictionary, which can be extended with your own,
+//! loaded from TOML (with the `toml` feature), or JSON (with the `json` feature):
+//!
+//! ```toml
+//! characters = ["SLA", "API"]
+//!
+//! [aliases]
+//! "approx." = "approximately"
+//! ```
+//!
+//! Abbreviations only match whole words. Ones written with an uppercase letter only match
+//! exactly as written, while ones written all in lowercase match regardless of case, so
+//! `approx.` also matches `Approx.`, but `SLA` doesn't match `sla`.
+
+#[cfg(any(feature = "json", feature = "toml"))]
+use failure::{Error, format_err};
+
+use std::collections::BTreeMap;
+#[cfg(any(feature = "json", feature = "toml"))]
+use std::fs;
+#[cfg(any(feature = "json", feature = "toml"))]
+use std::path::Path;
+
+use crate::parser::{OneItem, Span};
+use crate::ssml_constants::*;
+use crate::wrapping::{ends_word, rewrite_text, wrap_words};
+
+/// How an abbreviation should be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expansion {
+  /// Read as the given text, with a `sub` tag.
+  Alias(String),
+  /// Spelled out letter by letter, with a `say-as` tag.
+  Characters,
+}
+
+/// A single entry in a dictionary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Abbreviation {
+  /// The text as it's written.
+  pub text: String,
+  /// How it should be read.
+  pub expansion: Expansion,
+  /// If the text has to match exactly, or can match regardless of case.
+  pub case_sensitive: bool,
+}
+
+impl Abbreviation {
+
+  /// An abbreviation that's read as `alias`. It's case sensitive if `text` has any
+  /// uppercase letters.
+  pub fn alias(text: &str, alias: &str) -> Abbreviation {
+    Abbreviation {
+      text: text.to_owned(),
+      expansion: Expansion::Alias(alias.to_owned()),
+      case_sensitive: text.chars().any(char::is_uppercase),
+    }
+  }
+
+  /// An acronym that's spelled out. It's case sensitive if `text` has any uppercase
+  /// letters.
+  pub fn characters(text: &str) -> Abbreviation {
+    Abbreviation {
+      text: text.to_owned(),
+      expansion: Expansion::Characters,
+      case_sensitive: text.chars().any(char::is_uppercase),
+    }
+  }
+
+  /// How many bytes at the start of `text` this matches, if any.
+  fn matches(&self, text: &str) -> Option<usize> {
+    if self.case_sensitive {
+      return if text.starts_with(&self.text) { Some(self.text.len()) } else { None };
+    }
+
+    let mut characters = text.char_indices();
+    for expected in self.text.chars() {
+      match characters.next() {
+        Some((_, found)) if found.to_lowercase().eq(expected.to_lowercase()) => {},
+        _ => return None,
+      };
+    }
+    Some(characters.next().map_or(text.len(), |(index, _)| index))
+  }
+
+}
+
+/// The abbreviations built into each language, as (text, alias) pairs. An empty alias
+/// means the text is spelled out.
+const BUILT_IN: &[(&str, &[(&str, &str)])] = &[
+  ("en", &[
+    ("e.g.", "for example"), ("i.e.", "that is"), ("etc.", "et cetera"), ("approx.", "approximately"),
+    ("vs.", "versus"), ("incl.", "including"), ("min.", "minimum"), ("max.", "maximum"),
+    ("API", ""), ("SLA", ""), ("URL", ""), ("FAQ", ""), ("CEO", ""), ("CPU", ""), ("HTML", ""),
+    ("PDF", ""), ("SMS", ""), ("USB", ""),
+  ]),
+  ("de", &[
+    ("z.B.", "zum Beispiel"), ("d.h.", "das heißt"), ("usw.", "und so weiter"), ("ca.", "circa"),
+    ("bzw.", "beziehungsweise"), ("ggf.", "gegebenenfalls"), ("inkl.", "inklusive"),
+    ("API", ""), ("PDF", ""), ("URL", ""),
+  ]),
+  ("fr", &[
+    ("p.ex.", "par exemple"), ("c.-à-d.", "c'est-à-dire"), ("env.", "environ"),
+    ("etc.", "et cetera"), ("API", ""), ("PDF", ""), ("URL", ""),
+  ]),
+  ("es", &[
+    ("p.ej.", "por ejemplo"), ("aprox.", "aproximadamente"), ("etc.", "etcétera"),
+    ("API", ""), ("PDF", ""), ("URL", ""),
+  ]),
+];
+
+/// The layout of a dictionary file.
+#[cfg(any(feature = "json", feature = "toml"))]
+#[derive(serde::Deserialize)]
+struct DictionaryFile {
+  #[serde(default)]
+  case_sensitive: Option<bool>,
+  #[serde(default)]
+  characters: Vec<String>,
+  #[serde(default)]
+  aliases: BTreeMap<String, String>,
+}
+
+#[cfg(any(feature = "json", feature = "toml"))]
+impl From<DictionaryFile> for AbbreviationDictionary {
+  fn from(file: DictionaryFile) -> AbbreviationDictionary {
+    let mut dictionary = AbbreviationDictionary::default();
+    for text in &file.characters {
+      dictionary.insert(Abbreviation::characters(text));
+    }
+    for (text, alias) in &file.aliases {
+      dictionary.insert(Abbreviation::alias(text, alias));
+    }
+    if let Some(case_sensitive) = file.case_sensitive {
+      for abbreviation in &mut dictionary.abbreviations {
+        abbreviation.case_sensitive = case_sensitive;
+      }
+    }
+    dictionary
+  }
+}
+
+/// A set of abbreviations to expand while parsing.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AbbreviationDictionary {
+  /// Every abbreviation, when two match the same text the longer one wins, and after that
+  /// the one that comes first.
+  pub abbreviations: Vec<Abbreviation>,
+}
+
+impl AbbreviationDictionary {
+
+  /// The built-in dictionary for a language, such as `en-US`. Only the primary language is
+  /// looked at, and languages without one get an empty dictionary.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::abbreviations::{AbbreviationDictionary, Expansion};
+  /// let dictionary = AbbreviationDictionary::built_in("en-GB");
+  /// assert_eq!(dictionary.lookup("E.g.").map(|found| &found.expansion),
+  ///   Some(&Expansion::Alias("for example".to_owned())));
+  /// assert_eq!(dictionary.lookup("SLA").map(|found| &found.expansion), Some(&Expansion::Characters));
+  /// assert_eq!(dictionary.lookup("sla"), None);
+  /// assert!(AbbreviationDictionary::built_in("tlh").abbreviations.is_empty());
+  /// ```
+  pub fn built_in(lang: &str) -> AbbreviationDictionary {
+    let primary = lang.split(['-', '_']).next().unwrap_or("");
+    let mut dictionary = AbbreviationDictionary::default();
+    if let Some((_, entries)) = BUILT_IN.iter().find(|(language, _)| language.eq_ignore_ascii_case(primary)) {
+      for &(text, alias) in entries.iter() {
+        dictionary.insert(if alias.is_empty() { Abbreviation::characters(text) } else { Abbreviation::alias(text, alias) });
+      }
+    }
+    dictionary
+  }
+
+  /// Loads a dictionary from TOML.
+  #[cfg(feature = "toml")]
+  pub fn from_toml(data: &str) -> Result<AbbreviationDictionary, Error> {
+    Ok(toml::from_str::<DictionaryFile>(data)?.into())
+  }
+
+  /// Loads a dictionary from JSON, laid out the same way as the TOML.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::abbreviations::{AbbreviationDictionary, Expansion};
+  /// let dictionary = AbbreviationDictionary::from_json(
+  ///   r#"{"characters": ["SLA"], "aliases": {"approx.": "approximately"}}"#).unwrap();
+  /// assert_eq!(dictionary.abbreviations.len(), 2);
+  /// assert_eq!(dictionary.lookup("Approx.").map(|found| &found.expansion),
+  ///   Some(&Expansion::Alias("approximately".to_owned())));
+  /// ```
+  #[cfg(feature = "json")]
+  pub fn from_json(data: &str) -> Result<AbbreviationDictionary, Error> {
+    Ok(serde_json::from_str::<DictionaryFile>(data)?.into())
+  }
+
+  /// Loads a dictionary from a file, as TOML, or JSON depending on it's extension.
+  #[cfg(any(feature = "json", feature = "toml"))]
+  pub fn from_file<P: AsRef<Path>>(path: P) -> Result<AbbreviationDictionary, Error> {
+    let path = path.as_ref();
+    let data = fs::read_to_string(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+      #[cfg(feature = "toml")]
+      Some("toml") => AbbreviationDictionary::from_toml(&data),
+      #[cfg(feature = "json")]
+      Some("json") => AbbreviationDictionary::from_json(&data),
+      _ => Err(format_err!("Don't know how to load an abbreviation dictionary from: {}", path.display())),
+    }
+  }
+
+  /// Adds an abbreviation, replacing any with the same text.
+  pub fn insert(&mut self, abbreviation: Abbreviation) {
+    match self.abbreviations.iter_mut().find(|existing| existing.text == abbreviation.text) {
+      Some(existing) => *existing = abbreviation,
+      None => self.abbreviations.push(abbreviation),
+    };
+  }
+
+  /// Adds every abbreviation from `other`, replacing any with the same text. Useful for
+  /// layering your own dictionary over a built-in one.
+  pub fn extend(&mut self, other: AbbreviationDictionary) {
+    for abbreviation in other.abbreviations {
+      self.insert(abbreviation);
+    }
+  }
+
+  /// Finds the abbreviation that matches all of `text`.
+  pub fn lookup(&self, text: &str) -> Option<&Abbreviation> {
+    self.abbreviations.iter().find(|abbreviation| abbreviation.matches(text) == Some(text.len()))
+  }
+
+}
+
+/// Wraps every abbreviation found in the text tokens in a `sub`, or `say-as` tag. Text
+/// that's already inside of a `sub`, `say-as`, or `phoneme` tag is left alone.
+pub(crate) fn apply_abbreviations(items: Vec<(Span, OneItem)>, dictionary: &AbbreviationDictionary) -> Vec<(Span, OneItem)> {
+  let mut abbreviations = dictionary.abbreviations.iter()
+    .filter(|abbreviation| !abbreviation.text.is_empty())
+    .collect::<Vec<&Abbreviation>>();
+  abbreviations.sort_by_key(|abbreviation| std::cmp::Reverse(abbreviation.text.len()));
+
+  let skip = |tag_key: &str| matches!(tag_key.parse::<PossibleOpenTags>(),
+    Ok(PossibleOpenTags::Phoneme) | Ok(PossibleOpenTags::Sub) | Ok(PossibleOpenTags::SayAs));
+  rewrite_text(items, skip, |text, span, result| {
+    wrap_words(text, span.start, |rest| find_abbreviation(rest, &abbreviations), result);
+  })
+}
+
+/// Finds the abbreviation at the start of `rest`, along with the tag to wrap it in.
+fn find_abbreviation(rest: &str, abbreviations: &[&Abbreviation]) -> Option<(usize, String, BTreeMap<String, String>)> {
+  let (abbreviation, length) = abbreviations.iter().find_map(|abbreviation| {
+    abbreviation.matches(rest)
+      .filter(|&length| ends_word(rest, length))
+      .map(|length| (*abbreviation, length))
+  })?;
+
+  let mut params = BTreeMap::new();
+  let tag_key = match abbreviation.expansion {
+    Expansion::Alias(ref alias) => {
+      params.insert("alias".to_owned(), alias.to_owned());
+      PossibleOpenTags::Sub.to_string()
+    },
+    Expansion::Characters => {
+      params.insert("interpret-as".to_owned(), "characters".to_owned());
+      PossibleOpenTags::SayAs.to_string()
+    },
+  };
+  Some((length, tag_key, params))
+}
@@ -0,0 +1,353 @@
+//! Checks the sources of `audio` tags. Every source has to be an `http`, `https`, or
+//! `file` URL, or a path relative to where the audio files live. When building offline
+//! `ParseOptions::audio` can be set, so local files are checked as well, catching missing,
+//! or unplayable sound files when the markup is written rather than when it's spoken.
+//!
+//! Local files are checked by reading their headers, only MP3, and WAV files are
+//! recognized.
+
+use failure::{Error, format_err};
+
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::diagnostics::Diagnostic;
+use crate::parser::{OneItem, ParseOptions, Span};
+
+/// The URL schemes an audio source can use. Anything without a scheme is a relative path.
+const URL_SCHEMES: &[&str] = &["http", "https", "file"];
+
+/// How much of a file is searched for the first MP3 frame, after any ID3 tag.
+const MAX_HEADER_LENGTH: u64 = 64 * 1024;
+
+/// The sample rates of each MPEG version, by the index in the frame header.
+const MPEG1_SAMPLE_RATES: [u32; 3] = [44_100, 48_000, 32_000];
+const MPEG2_SAMPLE_RATES: [u32; 3] = [22_050, 24_000, 16_000];
+const MPEG25_SAMPLE_RATES: [u32; 3] = [11_025, 12_000, 8_000];
+
+/// The formats audio files are recognized in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AudioFormat {
+  Mp3,
+  Wav,
+}
+
+impl fmt::Display for AudioFormat {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      AudioFormat::Mp3 => write!(f, "MP3"),
+      AudioFormat::Wav => write!(f, "WAV"),
+    }
+  }
+}
+
+/// What was read out of an audio file's header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AudioInfo {
+  /// The format of the file.
+  pub format: AudioFormat,
+  /// The sample rate, in hertz.
+  pub sample_rate: u32,
+  /// The number of channels.
+  pub channels: u16,
+}
+
+/// Options for checking audio files while building offline.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AudioOptions {
+  /// The directory relative paths are resolved against.
+  pub directory: PathBuf,
+  /// The formats the engine can play.
+  pub formats: Vec<AudioFormat>,
+  /// The sample rates (in hertz) the engine can play.
+  pub sample_rates: Vec<u32>,
+}
+
+impl AudioOptions {
+
+  /// Checks audio files in `directory`, allowing MP3, and WAV files at any of the common
+  /// sample rates.
+  pub fn new<P: Into<PathBuf>>(directory: P) -> AudioOptions {
+    AudioOptions {
+      directory: directory.into(),
+      formats: vec![AudioFormat::Mp3, AudioFormat::Wav],
+      sample_rates: vec![8_000, 16_000, 22_050, 24_000, 32_000, 44_100, 48_000],
+    }
+  }
+
+  /// Checks audio files in `directory` against what Alexa can play, which is MP3 files at
+  /// 16000, 22050, or 24000 hertz.
+  pub fn alexa<P: Into<PathBuf>>(directory: P) -> AudioOptions {
+    AudioOptions {
+      directory: directory.into(),
+      formats: vec![AudioFormat::Mp3],
+      sample_rates: vec![16_000, 22_050, 24_000],
+    }
+  }
+
+  /// Where a `file` URL, or relative path points on disk, or `None` for anything that has
+  /// to be fetched, including `file` URLs naming another host.
+  fn local_path(&self, src: &str) -> Option<PathBuf> {
+    match url_scheme(src) {
+      Some(scheme) if scheme.eq_ignore_ascii_case("file") => {
+        let mut path = &src[scheme.len() + 1..];
+        if let Some(authority) = path.strip_prefix("//") {
+          let host_end = authority.find('/').unwrap_or(authority.len());
+          let host = &authority[..host_end];
+          if !host.is_empty() && !host.eq_ignore_ascii_case("localhost") {
+            return None;
+          }
+          path = &authority[host_end..];
+        }
+        Some(PathBuf::from(percent_decode(path)))
+      },
+      Some(_) => None,
+      None => Some(self.directory.join(percent_decode(src))),
+    }
+  }
+
+}
+
+/// Decodes the `%XX` escapes in a URL's path. Anything that isn't a valid escape is kept
+/// as is.
+fn percent_decode(path: &str) -> String {
+  let bytes = path.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut index = 0;
+  while index < bytes.len() {
+    let escaped = bytes.get(index + 1..index + 3)
+      .filter(|hex| bytes[index] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+      .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+    match escaped {
+      Some(byte) => {
+        decoded.push(byte);
+        index += 3;
+      },
+      None => {
+        decoded.push(bytes[index]);
+        index += 1;
+      },
+    }
+  }
+  String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Reads the header of the MP3, or WAV file at `path`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::audio::read_audio_info;
+/// assert!(read_audio_info("Cargo.toml").is_err());
+/// ```
+pub fn read_audio_info<P: AsRef<Path>>(path: P) -> Result<AudioInfo, Error> {
+  let path = path.as_ref();
+  let mut file = File::open(path)?;
+  let mut header = Vec::new();
+  (&mut file).take(12).read_to_end(&mut header)?;
+
+  if header.len() == 12 && &header[..4] == b"RIFF" && &header[8..] == b"WAVE" {
+    header.clear();
+    file.take(MAX_HEADER_LENGTH).read_to_end(&mut header)?;
+    return read_wav_format(&header).ok_or_else(|| format_err!("{} has no WAV format chunk.", path.display()));
+  }
+
+  let mut start = 0;
+  if header.len() == 12 && &header[..3] == b"ID3" {
+    let tag_length = header[6..10].iter().fold(0u64, |length, byte| (length << 7) | u64::from(byte & 0x7f));
+    start = 10 + tag_length;
+  }
+  file.seek(SeekFrom::Start(start))?;
+  header.clear();
+  file.take(MAX_HEADER_LENGTH).read_to_end(&mut header)?;
+  read_mp3_frame(&header).ok_or_else(|| format_err!("{} isn't an MP3, or WAV file.", path.display()))
+}
+
+/// Reads the `fmt ` chunk out of the chunks of a WAV file.
+fn read_wav_format(chunks: &[u8]) -> Option<AudioInfo> {
+  let mut rest = chunks;
+  while rest.len() >= 8 {
+    let length = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+    if &rest[..4] == b"fmt " && rest.len() >= 16 {
+      return Some(AudioInfo {
+        format: AudioFormat::Wav,
+        channels: u16::from_le_bytes([rest[10], rest[11]]),
+        sample_rate: u32::from_le_bytes([rest[12], rest[13], rest[14], rest[15]]),
+      });
+    }
+    // Chunks are padded to an even length.
+    rest = rest.get(length.checked_add(8 + length % 2)?..)?;
+  }
+  None
+}
+
+/// Finds the first MPEG layer III frame header.
+fn read_mp3_frame(data: &[u8]) -> Option<AudioInfo> {
+  data.windows(4).find_map(|frame| {
+    if frame[0] != 0xff || frame[1] & 0xe0 != 0xe0 {
+      return None;
+    }
+    let sample_rates = match (frame[1] >> 3) & 0b11 {
+      0b00 => MPEG25_SAMPLE_RATES,
+      0b10 => MPEG2_SAMPLE_RATES,
+      0b11 => MPEG1_SAMPLE_RATES,
+      _ => return None,
+    };
+    let layer_three = (frame[1] >> 1) & 0b11 == 0b01;
+    let bitrate = frame[2] >> 4;
+    if !layer_three || bitrate == 0b1111 {
+      return None;
+    }
+    let sample_rate = *sample_rates.get(usize::from((frame[2] >> 2) & 0b11))?;
+    Some(AudioInfo {
+      format: AudioFormat::Mp3,
+      sample_rate,
+      channels: if frame[3] >> 6 == 0b11 { 1 } else { 2 },
+    })
+  })
+}
+
+/// The scheme of `src` if it's a URL, or `None` if it's a path.
+fn url_scheme(src: &str) -> Option<&str> {
+  let (scheme, _) = src.split_once(':')?;
+  let mut chars = scheme.chars();
+  let is_scheme = chars.next().is_some_and(|first| first.is_ascii_alphabetic()) &&
+    chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+  // A single letter is a Windows drive (`C:\sounds\bell.mp3`), not a scheme.
+  if is_scheme && scheme.len() > 1 {
+    Some(scheme)
+  } else {
+    None
+  }
+}
+
+/// Finds what's wrong with the audio source `src`, if anything.
+fn check_source(src: &str, options: Option<&AudioOptions>) -> Option<String> {
+  if let Some(scheme) = url_scheme(src) {
+    if !URL_SCHEMES.iter().any(|allowed| scheme.eq_ignore_ascii_case(allowed)) {
+      return Some(format!("\"{}\" isn't an http, https, or file URL, or a relative path.", src));
+    }
+  }
+
+  let options = options?;
+  let path = options.local_path(src)?;
+  if !path.is_file() {
+    return Some(format!("The audio file \"{}\" doesn't exist.", path.display()));
+  }
+  let info = match read_audio_info(&path) {
+    Ok(info) => info,
+    Err(_) => return Some(format!("\"{}\" isn't an MP3, or WAV file.", path.display())),
+  };
+  if !options.formats.contains(&info.format) {
+    return Some(format!("\"{}\" is a {} file, which isn't supported.", path.display(), info.format));
+  }
+  if !options.sample_rates.contains(&info.sample_rate) {
+    return Some(format!("\"{}\" has a sample rate of {}Hz, which isn't supported.", path.display(), info.sample_rate));
+  }
+  None
+}
+
+/// If `item` starts, or ends a tag named `name`.
+fn is_tag(item: &OneItem, name: &str) -> bool {
+  item.start_tag.as_ref().map(|start_tag| &start_tag.tag_key)
+    .or_else(|| item.end_tag.as_ref().map(|end_tag| &end_tag.tag_key))
+    .is_some_and(|tag_key| tag_key.eq_ignore_ascii_case(name))
+}
+
+/// If `item` is an `${audio}` start tag.
+pub(crate) fn is_audio_start(item: &OneItem) -> bool {
+  item.start_tag.is_some() && is_tag(item, "audio")
+}
+
+/// If `item` is an `${/audio}` end tag.
+pub(crate) fn is_audio_end(item: &OneItem) -> bool {
+  item.end_tag.is_some() && is_tag(item, "audio")
+}
+
+/// If `item` can be inside of an audio tag. Only text, and `desc` tags can be.
+pub(crate) fn is_fallback(item: &OneItem) -> bool {
+  (item.start_tag.is_none() && item.end_tag.is_none()) || is_tag(item, "desc")
+}
+
+/// If the audio tag right before `rest` is closed by an `${/audio}`, making everything in
+/// between it's fallback. Anything that can't be inside of an audio tag means the audio
+/// tag stands on it's own. `None` if there's no way to tell yet.
+pub(crate) fn audio_is_closed<'a, I: IntoIterator<Item = &'a OneItem>>(rest: I) -> Option<bool> {
+  rest.into_iter().find(|item| !is_fallback(item)).map(is_audio_end)
+}
+
+/// Closes every audio tag that stands on it's own (`${audio|src=...}` without any
+/// fallback), so every dialect sees a start, and end tag.
+pub(crate) fn close_audio_tags(items: Vec<(Span, OneItem)>) -> Vec<(Span, OneItem)> {
+  let mut closed = Vec::with_capacity(items.len());
+  for (index, (span, item)) in items.iter().enumerate() {
+    closed.push((*span, item.clone()));
+    if is_audio_start(item) && audio_is_closed(items[index + 1..].iter().map(|(_, item)| item)) != Some(true) {
+      closed.push((*span, OneItem::end("audio")));
+    }
+  }
+  closed
+}
+
+/// Checks the source of every audio tag. In strict mode a bad source is an error,
+/// otherwise the audio tag is left out with a warning, keeping it's fallback text.
+pub(crate) fn check_audio(items: Vec<(Span, OneItem)>, options: &ParseOptions,
+  diagnostics: &mut Vec<Diagnostic>) -> Result<Vec<(Span, OneItem)>, Error> {
+  let mut check = AudioCheck::default();
+  let mut checked = Vec::with_capacity(items.len());
+  for (span, item) in items {
+    if check.keep(span, &item, options.audio.as_ref(), options.strict, diagnostics)? {
+      checked.push((span, item));
+    }
+  }
+  Ok(checked)
+}
+
+/// Checks audio sources one token at a time, so it works on a stream as well. Audio tags
+/// with a bad source are left out along with their `desc`, keeping their fallback text.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AudioCheck {
+  leaving_out: bool,
+  in_desc: bool,
+}
+
+impl AudioCheck {
+
+  /// If `item` should be kept. In strict mode a bad source is an error instead.
+  pub(crate) fn keep(&mut self, span: Span, item: &OneItem, options: Option<&AudioOptions>, strict: bool,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<bool, Error> {
+    if self.leaving_out {
+      if is_tag(item, "desc") {
+        self.in_desc = item.start_tag.is_some();
+        return Ok(false);
+      }
+      if self.in_desc {
+        return Ok(false);
+      }
+      if is_audio_end(item) {
+        self.leaving_out = false;
+        return Ok(false);
+      }
+      self.leaving_out = is_fallback(item);
+    }
+
+    if let Some(start_tag) = item.start_tag.as_ref().filter(|_| is_audio_start(item)) {
+      let problem = match start_tag.params.get("src") {
+        Some(src) => check_source(src, options),
+        None => Some("<audio> is missing \"src\".".to_owned()),
+      };
+      if let Some(message) = problem {
+        if strict {
+          return Err(format_err!("{} ({}..{})", message, span.start, span.end));
+        }
+        diagnostics.push(Diagnostic::warning(format!("{} The audio was left out.", message), Some(span)));
+        self.leaving_out = true;
+        return Ok(false);
+      }
+    }
+    Ok(true)
+  }
+
+}
@@ -0,0 +1,360 @@
+//! A fluent builder for putting together SSML in code. Rather than pairing every
+//! `start_ssml_*` with the matching `end_ssml_*` on an `XmlWriter` by hand, each tag
+//! that can hold content takes a closure that fills in that content. This way it's
+//! impossible to end up with tags that are never closed, or are closed out of order.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use text_to_polly_ssml::builder::*;
+//! use text_to_polly_ssml::ssml_constants::*;
+//!
+//! let ssml = Ssml::speak()
+//!   .text("hi")
+//!   .prosody(|p| p.rate(ProsodyRate::Fast).text("quick"))
+//!   .brk(BreakTime::new(500, false))
+//!   .render();
+//! assert_eq!(ssml.unwrap(), r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">hi<prosody rate="fast">quick</prosody><break time="500ms"/></speak>"#);
+//! ```
+
+use failure::Error;
+
+use std::io::Write;
+
+use crate::ssml_constants::*;
+use crate::xml_writer::XmlWriter;
+
+/// A single piece of content inside of an SSML document. These are only ever created
+/// through the methods on `Container`.
+pub struct Node(NodeKind);
+
+enum NodeKind {
+  Text(String),
+  Break(Option<BreakStrength>, Option<BreakTime>),
+  Breath(BreathVolumes, BreathDuration),
+  Element(Element, Vec<Node>),
+}
+
+enum Element {
+  Lang(String, Option<String>),
+  Mark(String),
+  Paragraph,
+  Phoneme(PhonemeAlphabet, String),
+  Prosody(Option<String>, Option<ProsodyRate>, Option<String>),
+  Sentence,
+  SayAs(String),
+  Sub(String),
+  Word(WordRole),
+  Emphasis(Option<EmphasisLevel>),
+  AmazonEffect(AmazonEffect),
+  VocalTractLength(String),
+  Phonation(PhonationVolume),
+  AutoBreaths(BreathVolumes, AutoBreathFrequency, BreathDuration),
+}
+
+/// Anything that can hold SSML content. All of the methods for adding content live here,
+/// so they're available both at the root of the document, and inside of any tag.
+pub trait Container: Sized {
+
+  /// The content that has been added so far. You shouldn't need to call this yourself.
+  #[doc(hidden)]
+  fn nodes_mut(&mut self) -> &mut Vec<Node>;
+
+  /// Adds some raw text.
+  fn text(mut self, text: &str) -> Self {
+    self.nodes_mut().push(Node(NodeKind::Text(text.to_owned())));
+    self
+  }
+
+  /// Adds a `<break>` tag with a specific amount of time.
+  fn brk(mut self, time: BreakTime) -> Self {
+    self.nodes_mut().push(Node(NodeKind::Break(None, Some(time))));
+    self
+  }
+
+  /// Adds a `<break>` tag with a specific strength.
+  fn brk_strength(mut self, strength: BreakStrength) -> Self {
+    self.nodes_mut().push(Node(NodeKind::Break(Some(strength), None)));
+    self
+  }
+
+  /// Adds an `<amazon:breath>` tag.
+  fn breath(mut self, volume: BreathVolumes, duration: BreathDuration) -> Self {
+    self.nodes_mut().push(Node(NodeKind::Breath(volume, duration)));
+    self
+  }
+
+  /// Adds a `<lang>` tag, containing whatever `content` adds.
+  fn lang<F>(self, lang: &str, onlangfailure: Option<&str>, content: F) -> Self
+    where F: FnOnce(Content) -> Content {
+    push_element(self, Element::Lang(lang.to_owned(), onlangfailure.map(str::to_owned)), content)
+  }
+
+  /// Adds a `<mark>` tag, containing whatever `content` adds.
+  fn mark<F>(self, name: &str, content: F) -> Self where F: FnOnce(Content) -> Content {
+    push_element(self, Element::Mark(name.to_owned()), content)
+  }
+
+  /// Adds a `<p>` tag, containing whatever `content` adds.
+  fn paragraph<F>(self, content: F) -> Self where F: FnOnce(Content) -> Content {
+    push_element(self, Element::Paragraph, content)
+  }
+
+  /// Adds a `<phoneme>` tag, containing whatever `content` adds.
+  fn phoneme<F>(self, alphabet: PhonemeAlphabet, ph: &str, content: F) -> Self
+    where F: FnOnce(Content) -> Content {
+    push_element(self, Element::Phoneme(alphabet, ph.to_owned()), content)
+  }
+
+  /// Adds a `<prosody>` tag. The closure gets a `Prosody` which can both set the
+  /// attributes of the tag, and add the content inside of it. At least one of the
+  /// attributes needs to be set, otherwise rendering will fail.
+  fn prosody<F>(mut self, content: F) -> Self where F: FnOnce(Prosody) -> Prosody {
+    let prosody = content(Prosody::default());
+    let element = Element::Prosody(prosody.volume, prosody.rate, prosody.pitch);
+    self.nodes_mut().push(Node(NodeKind::Element(element, prosody.nodes)));
+    self
+  }
+
+  /// Adds a `<s>` tag, containing whatever `content` adds.
+  fn sentence<F>(self, content: F) -> Self where F: FnOnce(Content) -> Content {
+    push_element(self, Element::Sentence, content)
+  }
+
+  /// Adds a `<say-as>` tag, containing whatever `content` adds.
+  fn say_as<F>(self, interpret_as: &str, content: F) -> Self where F: FnOnce(Content) -> Content {
+    push_element(self, Element::SayAs(interpret_as.to_owned()), content)
+  }
+
+  /// Adds a `<sub>` tag, containing whatever `content` adds.
+  fn sub<F>(self, alias: &str, content: F) -> Self where F: FnOnce(Content) -> Content {
+    push_element(self, Element::Sub(alias.to_owned()), content)
+  }
+
+  /// Adds a `<w>` tag, containing whatever `content` adds.
+  fn w<F>(self, role: WordRole, content: F) -> Self where F: FnOnce(Content) -> Content {
+    push_element(self, Element::Word(role), content)
+  }
+
+  /// Adds an `<emphasis>` tag, containing whatever `content` adds.
+  fn emphasis<F>(self, level: Option<EmphasisLevel>, content: F) -> Self
+    where F: FnOnce(Content) -> Content {
+    push_element(self, Element::Emphasis(level), content)
+  }
+
+  /// Adds an `<amazon:effect name="...">` tag, containing whatever `content` adds.
+  fn amazon_effect<F>(self, name: AmazonEffect, content: F) -> Self
+    where F: FnOnce(Content) -> Content {
+    push_element(self, Element::AmazonEffect(name), content)
+  }
+
+  /// Adds an `<amazon:effect vocal-tract-length="...">` tag, containing whatever `content` adds.
+  fn vocal_tract_length<F>(self, factor: &str, content: F) -> Self
+    where F: FnOnce(Content) -> Content {
+    push_element(self, Element::VocalTractLength(factor.to_owned()), content)
+  }
+
+  /// Adds an `<amazon:effect phonation="...">` tag, containing whatever `content` adds.
+  fn phonation<F>(self, volume: PhonationVolume, content: F) -> Self
+    where F: FnOnce(Content) -> Content {
+    push_element(self, Element::Phonation(volume), content)
+  }
+
+  /// Adds an `<amazon:auto-breaths>` tag, containing whatever `content` adds.
+  fn auto_breaths<F>(self, volume: BreathVolumes, frequency: AutoBreathFrequency,
+    duration: BreathDuration, content: F) -> Self where F: FnOnce(Content) -> Content {
+    push_element(self, Element::AutoBreaths(volume, frequency, duration), content)
+  }
+
+}
+
+/// The root of an SSML Document, the `<speak>` tag.
+pub struct Ssml {
+  lang: Option<String>,
+  onlangfailure: Option<String>,
+  nodes: Vec<Node>,
+}
+
+impl Ssml {
+
+  /// Starts a new document, with the default language.
+  pub fn speak() -> Ssml {
+    Ssml {
+      lang: None,
+      onlangfailure: None,
+      nodes: Vec::new(),
+    }
+  }
+
+  /// Starts a new document in a specific language.
+  pub fn speak_with_lang(lang: &str, onlangfailure: Option<&str>) -> Ssml {
+    Ssml {
+      lang: Some(lang.to_owned()),
+      onlangfailure: onlangfailure.map(str::to_owned),
+      nodes: Vec::new(),
+    }
+  }
+
+  /// Renders the document out to a String of SSML.
+  pub fn render(self) -> Result<String, Error> {
+    let mut xml_writer = XmlWriter::new()?;
+    self.write_to(&mut xml_writer)?;
+    xml_writer.into_string()
+  }
+
+  /// Writes the document into an existing XML Writer.
+  pub fn write_to<W: Write>(self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.start_ssml_speak(self.lang, self.onlangfailure)?;
+    write_nodes(xml_writer, self.nodes)?;
+    xml_writer.end_ssml_speak()?;
+    Ok(())
+  }
+
+}
+
+impl Container for Ssml {
+  fn nodes_mut(&mut self) -> &mut Vec<Node> {
+    &mut self.nodes
+  }
+}
+
+/// The content inside of a tag.
+#[derive(Default)]
+pub struct Content {
+  nodes: Vec<Node>,
+}
+
+impl Container for Content {
+  fn nodes_mut(&mut self) -> &mut Vec<Node> {
+    &mut self.nodes
+  }
+}
+
+/// The content inside of a `<prosody>` tag, along with it's attributes.
+#[derive(Default)]
+pub struct Prosody {
+  volume: Option<String>,
+  rate: Option<ProsodyRate>,
+  pitch: Option<String>,
+  nodes: Vec<Node>,
+}
+
+impl Prosody {
+
+  /// Sets the volume of the prosody tag, e.g. `+6dB`.
+  pub fn volume(mut self, volume: &str) -> Prosody {
+    self.volume = Some(volume.to_owned());
+    self
+  }
+
+  /// Sets the rate of the prosody tag.
+  pub fn rate(mut self, rate: ProsodyRate) -> Prosody {
+    self.rate = Some(rate);
+    self
+  }
+
+  /// Sets the pitch of the prosody tag, e.g. `+10%`.
+  pub fn pitch(mut self, pitch: &str) -> Prosody {
+    self.pitch = Some(pitch.to_owned());
+    self
+  }
+
+}
+
+impl Container for Prosody {
+  fn nodes_mut(&mut self) -> &mut Vec<Node> {
+    &mut self.nodes
+  }
+}
+
+fn push_element<C, F>(mut container: C, element: Element, content: F) -> C
+  where C: Container, F: FnOnce(Content) -> Content {
+  let content = content(Content::default());
+  container.nodes_mut().push(Node(NodeKind::Element(element, content.nodes)));
+  container
+}
+
+fn write_nodes<W: Write>(xml_writer: &mut XmlWriter<W>, nodes: Vec<Node>) -> Result<(), Error> {
+  for Node(node) in nodes {
+    match node {
+      NodeKind::Text(text) => { xml_writer.write_text(&text)?; },
+      NodeKind::Break(strength, time) => { xml_writer.ssml_break(strength, time)?; },
+      NodeKind::Breath(volume, duration) => { xml_writer.write_amazon_breath(volume, duration)?; },
+      NodeKind::Element(element, children) => {
+        match element {
+          Element::Lang(lang, onlangfailure) => {
+            xml_writer.start_ssml_lang(lang, onlangfailure)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_lang()?;
+          },
+          Element::Mark(name) => {
+            xml_writer.start_ssml_mark(name)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_mark()?;
+          },
+          Element::Paragraph => {
+            xml_writer.start_ssml_paragraph()?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_paragraph()?;
+          },
+          Element::Phoneme(alphabet, ph) => {
+            xml_writer.start_ssml_phoneme(alphabet, ph)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_phoneme()?;
+          },
+          Element::Prosody(volume, rate, pitch) => {
+            xml_writer.start_ssml_prosody(volume, rate, pitch)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_prosody()?;
+          },
+          Element::Sentence => {
+            xml_writer.start_ssml_sentence()?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_sentence()?;
+          },
+          Element::SayAs(interpret_as) => {
+            xml_writer.start_ssml_say_as(interpret_as)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_say_as()?;
+          },
+          Element::Sub(alias) => {
+            xml_writer.start_ssml_sub(alias)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_sub()?;
+          },
+          Element::Word(role) => {
+            xml_writer.start_ssml_w(role)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_w()?;
+          },
+          Element::Emphasis(level) => {
+            xml_writer.start_ssml_emphasis(level)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_emphasis()?;
+          },
+          Element::AmazonEffect(name) => {
+            xml_writer.start_ssml_amazon_effect(name)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_amazon_effect()?;
+          },
+          Element::VocalTractLength(factor) => {
+            xml_writer.start_ssml_vocal_tract_length(factor)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_amazon_effect()?;
+          },
+          Element::Phonation(volume) => {
+            xml_writer.start_ssml_phonation(volume)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_amazon_effect()?;
+          },
+          Element::AutoBreaths(volume, frequency, duration) => {
+            xml_writer.start_ssml_auto_breaths(volume, frequency, duration)?;
+            write_nodes(xml_writer, children)?;
+            xml_writer.end_ssml_amazon_auto_breaths()?;
+          },
+        };
+      },
+    };
+  }
+  Ok(())
+}
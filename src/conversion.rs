@@ -0,0 +1,114 @@
+//! Shared by the converters from other formats (Markdown, and HTML), which build up the
+//! parser's tokens from a document's structure, reading headings, and list items the same.
+
+#[cfg(feature = "markdown")]
+use failure::Error;
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "markdown")]
+use crate::parser::tokenize_with_spans;
+use crate::parser::{OneItem, Span};
+use crate::ssml_constants::BreakStrength;
+
+/// The tokens converted so far, along with text that hasn't been written yet.
+pub(crate) struct Tokens {
+  pub(crate) items: Vec<(Span, OneItem)>,
+  /// Text that hasn't been written yet. It's collected until the next tag, so it ends up
+  /// in one piece.
+  pub(crate) text: Option<(Span, String)>,
+}
+
+impl Tokens {
+
+  pub(crate) fn new() -> Tokens {
+    Tokens {
+      items: Vec::new(),
+      text: None,
+    }
+  }
+
+  pub(crate) fn start(&mut self, span: Span, tag_key: &str, params: &[(&str, &str)]) {
+    let params = params.iter()
+      .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+      .collect::<BTreeMap<String, String>>();
+    self.items.push((span, OneItem::start(tag_key, params)));
+  }
+
+  pub(crate) fn end(&mut self, span: Span, tag_key: &str) {
+    self.items.push((span, OneItem::end(tag_key)));
+  }
+
+  pub(crate) fn pause(&mut self, span: Span, strength: BreakStrength) {
+    self.start(span, "break", &[("strength", &strength.to_string())]);
+  }
+
+  pub(crate) fn push_text(&mut self, span: Span, text: &str) {
+    match self.text {
+      Some((ref mut text_span, ref mut pending)) => {
+        text_span.end = text_span.end.max(span.end);
+        pending.push_str(text);
+      },
+      None => self.text = Some((span, text.to_owned())),
+    };
+  }
+
+  /// Writes any text collected so far as is.
+  #[cfg(feature = "html")]
+  pub(crate) fn flush_text(&mut self) {
+    if let Some((span, text)) = self.text.take() {
+      self.items.push((span, OneItem::text(&escape_markup(&text))));
+    }
+  }
+
+  /// Tokenizes any text collected so far, which can have markup in it.
+  #[cfg(feature = "markdown")]
+  pub(crate) fn flush_markup(&mut self) -> Result<(), Error> {
+    let (span, text) = match self.text.take() {
+      Some(pending) => pending,
+      None => return Ok(()),
+    };
+    if !text.contains("${") {
+      self.items.push((span, OneItem::text(&text)));
+      return Ok(());
+    }
+    for (item_span, item) in tokenize_with_spans(&text)? {
+      let start = (span.start + item_span.start).min(span.end);
+      let end = (span.start + item_span.end).min(span.end);
+      self.items.push((Span { start, end }, item));
+    }
+    Ok(())
+  }
+
+  /// Headings are read louder, and slower in a paragraph of their own, after a strong
+  /// break.
+  pub(crate) fn start_heading(&mut self, span: Span) {
+    self.pause(span, BreakStrength::Strong);
+    self.start(span, "p", &[]);
+    self.start(span, "prosody", &[("volume", "loud"), ("rate", "slow")]);
+  }
+
+  pub(crate) fn end_heading(&mut self, span: Span) {
+    self.end(span, "prosody");
+    self.end(span, "p");
+  }
+
+  /// Every list item is a sentence, followed by a break.
+  pub(crate) fn start_list_item(&mut self, span: Span) {
+    self.start(span, "s", &[]);
+  }
+
+  /// Ends a list item's sentence, which can come before the end of the item itself (e.g.
+  /// before a nested list).
+  pub(crate) fn end_list_item(&mut self, span: Span) {
+    self.end(span, "s");
+    self.pause(span, BreakStrength::Medium);
+  }
+
+}
+
+/// Text that's read as is has any markup in it escaped, which the writer turns back into
+/// a plain `${`.
+pub(crate) fn escape_markup(text: &str) -> String {
+  text.replace("${", "$\\{")
+}
@@ -0,0 +1,72 @@
+//! Diagnostics are problems we found along the way that didn't stop us from doing our job,
+//! like an SSML document using an attribute Polly doesn't know about. They're collected,
+//! and handed back to you to decide what to do with.
+
+use std::fmt;
+
+use crate::parser::Span;
+
+/// How bad a diagnostic is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Severity {
+  /// Something that will almost certainly be rejected, or ignored by the engine.
+  Error,
+  /// Something that will probably work, but might not do what you expect.
+  Warning,
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Severity::Error => write!(f, "error"),
+      Severity::Warning => write!(f, "warning"),
+    }
+  }
+}
+
+/// A single problem, along with where it was found if we know.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+  /// How bad the problem is.
+  pub severity: Severity,
+  /// A human readable description of the problem.
+  pub message: String,
+  /// Where in the input the problem was found, as byte offsets.
+  pub span: Option<Span>,
+}
+
+impl Diagnostic {
+
+  /// Creates a new error diagnostic.
+  pub fn error(message: String, span: Option<Span>) -> Diagnostic {
+    Diagnostic {
+      severity: Severity::Error,
+      message,
+      span,
+    }
+  }
+
+  /// Creates a new warning diagnostic.
+  pub fn warning(message: String, span: Option<Span>) -> Diagnostic {
+    Diagnostic {
+      severity: Severity::Warning,
+      message,
+      span,
+    }
+  }
+
+  /// If this diagnostic is an error.
+  pub fn is_error(&self) -> bool {
+    self.severity == Severity::Error
+  }
+
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.span {
+      Some(ref span) => write!(f, "{} at {}..{}: {}", self.severity, span.start, span.end, self.message),
+      None => write!(f, "{}: {}", self.severity, self.message),
+    }
+  }
+}
@@ -0,0 +1,190 @@
+//! The SSML the Alexa Skills Kit supports. The full list of tags, and limits can be found:
+//! [HERE](https://developer.amazon.com/docs/custom-skills/speech-synthesis-markup-language-ssml-reference.html).
+
+use failure::Error;
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::time::Duration;
+
+use crate::diagnostics::Diagnostic;
+use crate::parser::{EndTag, Span, StartTag};
+use crate::ssml_constants::*;
+use crate::xml_writer::XmlWriter;
+
+use super::{dropped_tag, Dialect, OpenTags, TagTable};
+
+/// The tags Alexa supports that are written as is, along with their required, and
+/// optional attributes.
+const TAGS: TagTable = &[
+  ("p", &[], &[]),
+  ("s", &[], &[]),
+  ("sub", &["alias"], &[]),
+  ("phoneme", &["alphabet", "ph"], &[]),
+  ("prosody", &[], &["rate", "pitch", "volume"]),
+  ("say-as", &["interpret-as"], &["format"]),
+  ("w", &["role"], &[]),
+  ("emphasis", &[], &["level"]),
+  ("voice", &["name"], &[]),
+  ("amazon:emotion", &["name", "intensity"], &[]),
+  ("amazon:domain", &["name"], &[]),
+];
+
+/// The most `<audio>` tags a single response can have.
+const MAX_AUDIO_CLIPS: usize = 5;
+
+/// The longest all of the audio in a single response can be.
+const MAX_AUDIO_DURATION: Duration = Duration::from_secs(240);
+
+/// The SSML for an Alexa skill's response. Alexa refuses the XML header, and any
+/// attributes on `<speak>`, so neither are written. On top of what Polly supports, Alexa
+/// supports `${amazon:emotion|name=...|intensity=...}`, `${amazon:domain|name=...}`,
+/// `${voice|name=...}`, and `${audio|src=...}`.
+///
+/// Alexa's `<audio>` can't hold any fallback text, so the text inside of
+/// `${audio|src=...}...${/audio}` is only said when the clip is left out.
+///
+/// Alexa only plays five audio clips in a response, for a total of 240 seconds. Clips past
+/// either limit are left out with a warning. Alexa can't tell us how long a clip is, so
+/// only the durations given with `with_audio_duration` count towards the total.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::dialect::AlexaDialect;
+/// use text_to_polly_ssml::parser::{parse_with_dialect, ParseOptions};
+/// let output = parse_with_dialect(
+///   "${amazon:emotion|name=excited|intensity=high}Yes!${/amazon:emotion} ${audio|src=https://example.com/horn.mp3}",
+///   &ParseOptions::default(),
+///   AlexaDialect::default(),
+/// ).unwrap();
+/// assert_eq!(output.ssml, concat!(
+///   r#"<speak><amazon:emotion name="excited" intensity="high">Yes!</amazon:emotion> "#,
+///   r#"<audio src="https://example.com/horn.mp3"/></speak>"#,
+/// ));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AlexaDialect {
+  open_tags: OpenTags,
+  audio_durations: BTreeMap<String, Duration>,
+  audio_clips: usize,
+  audio_duration: Duration,
+  in_audio: bool,
+}
+
+impl AlexaDialect {
+
+  /// Sets how long the audio at `src` is, so it counts towards Alexa's limit on the total
+  /// length of audio.
+  pub fn with_audio_duration(mut self, src: &str, duration: Duration) -> AlexaDialect {
+    self.audio_durations.insert(src.to_owned(), duration);
+    self
+  }
+
+  fn write_audio<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let src = match start_tag.params.get("src") {
+      Some(src) => src,
+      None => {
+        diagnostics.push(Diagnostic::warning("<audio> is missing \"src\", it was left out.".to_owned(), Some(span)));
+        return Ok(());
+      },
+    };
+
+    if self.audio_clips >= MAX_AUDIO_CLIPS {
+      diagnostics.push(Diagnostic::warning(
+        format!("Alexa only plays {} audio clips in a response, \"{}\" was left out.", MAX_AUDIO_CLIPS, src),
+        Some(span)));
+      return Ok(());
+    }
+    let duration = self.audio_durations.get(src).cloned().unwrap_or_default();
+    if self.audio_duration + duration > MAX_AUDIO_DURATION {
+      diagnostics.push(Diagnostic::warning(
+        format!("Alexa only plays {} seconds of audio in a response, \"{}\" was left out.",
+          MAX_AUDIO_DURATION.as_secs(), src),
+        Some(span)));
+      return Ok(());
+    }
+
+    self.audio_clips += 1;
+    self.audio_duration += duration;
+    self.in_audio = true;
+    xml_writer.write_empty_element("audio", &[("src", src)])?;
+    Ok(())
+  }
+
+}
+
+impl Dialect for AlexaDialect {
+
+  fn name(&self) -> &'static str {
+    "Alexa"
+  }
+
+  fn xml_declaration(&self) -> bool {
+    false
+  }
+
+  fn start_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.start_element("speak", &[])?;
+    Ok(())
+  }
+
+  fn end_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.end_element("speak")?;
+    Ok(())
+  }
+
+  fn start_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let name = start_tag.tag_key.to_lowercase();
+    let params = &start_tag.params;
+
+    match &*name {
+      "break" => {
+        let strength = params.get("strength").and_then(|strength| strength.parse::<BreakStrength>().ok());
+        let time = params.get("time").and_then(|time| time.parse::<BreakTime>().ok());
+        xml_writer.ssml_break(strength, time)?;
+      },
+      "audio" => self.write_audio(xml_writer, start_tag, span, diagnostics)?,
+      "lang" => match params.get("lang") {
+        Some(lang) => self.open_tags.start(xml_writer, start_tag, "lang", &[("xml:lang", lang)])?,
+        None => self.open_tags.leave_out(self.name(), start_tag, span, diagnostics),
+      },
+      "amazon:effect" => {
+        let whispered = params.get("name").and_then(|effect| effect.parse::<AmazonEffect>().ok()) ==
+          Some(AmazonEffect::Whispered);
+        if whispered {
+          self.open_tags.start(xml_writer, start_tag, "amazon:effect", &[("name", "whispered")])?;
+        } else {
+          self.open_tags.leave_out(self.name(), start_tag, span, diagnostics);
+        }
+      },
+      "desc" => {
+        diagnostics.push(dropped_tag(self.name(), &start_tag.tag_key, span));
+        self.open_tags.silence(start_tag);
+      },
+      "amazon:breath" => diagnostics.push(dropped_tag(self.name(), &start_tag.tag_key, span)),
+      _ => self.open_tags.start_from_table(xml_writer, TAGS, self.name(), start_tag, span, diagnostics)?,
+    };
+
+    Ok(())
+  }
+
+  fn end_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, end_tag: &EndTag, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if end_tag.tag_key.eq_ignore_ascii_case("audio") {
+      self.in_audio = false;
+    }
+    self.open_tags.close(xml_writer, &end_tag.tag_key)
+  }
+
+  fn text<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, text: &str, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if !self.in_audio && !self.open_tags.is_silenced() {
+      xml_writer.write_text(text)?;
+    }
+    Ok(())
+  }
+
+}
@@ -0,0 +1,323 @@
+//! The SSML Azure Speech supports. The full list of tags can be found:
+//! [HERE](https://learn.microsoft.com/azure/ai-services/speech-service/speech-synthesis-markup).
+
+use failure::Error;
+
+use std::io::Write;
+
+use crate::diagnostics::Diagnostic;
+use crate::parser::{EndTag, Span, StartTag};
+use crate::ssml_constants::*;
+use crate::xml_writer::XmlWriter;
+
+use super::{dropped_tag, Dialect, OpenTags, TagTable};
+
+/// The tags Azure supports that are written as is, along with their required, and
+/// optional attributes.
+const TAGS: TagTable = &[
+  ("p", &[], &[]),
+  ("s", &[], &[]),
+  ("sub", &["alias"], &[]),
+  ("phoneme", &["alphabet", "ph"], &[]),
+  ("say-as", &["interpret-as"], &["format", "detail"]),
+  ("emphasis", &[], &["level"]),
+  ("audio", &["src"], &[]),
+];
+
+/// Named volumes, rates, and pitches Polly, and Azure both support.
+const NAMED_PROSODY: &[&str] = &[
+  "default", "silent", "x-soft", "soft", "medium", "loud", "x-loud", "x-slow", "slow", "fast", "x-fast",
+  "x-low", "low", "high", "x-high",
+];
+
+/// The slowest, and fastest rates Azure supports, as a change in percent.
+const RATE_RANGE: (f64, f64) = (-50.0, 100.0);
+
+/// Azure Speech's SSML. Everything is spoken by a `<voice>`, which can't be nested, so the
+/// text is wrapped in the default voice given when creating the dialect, and a
+/// `${voice|name=...}` tag outside of any other tag switches to another voice until it's
+/// closed. Tags still open when the voice closes are closed with it, and opened again in
+/// the default voice. Microsoft's speaking styles can be used with `${express-as|style=...}`.
+///
+/// Polly's whispering is written as the `whispering` style, prosody is converted into
+/// the values Azure accepts, and everything else only Polly supports is left out with a
+/// warning.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::dialect::AzureDialect;
+/// use text_to_polly_ssml::parser::{parse_with_dialect, ParseOptions};
+/// let output = parse_with_dialect(
+///   "Hi ${voice|name=en-US-GuyNeural}${express-as|style=cheerful}there${/express-as}${/voice}",
+///   &ParseOptions::default(),
+///   AzureDialect::new("en-US-JennyNeural"),
+/// ).unwrap();
+/// assert!(output.ssml.ends_with(concat!(
+///   r#"<voice name="en-US-JennyNeural">Hi </voice><voice name="en-US-GuyNeural">"#,
+///   r#"<mstts:express-as style="cheerful">there</mstts:express-as></voice></speak>"#,
+/// )));
+/// ```
+#[derive(Clone, Debug)]
+pub struct AzureDialect {
+  voice: String,
+  lang: String,
+  open_tags: OpenTags,
+  voice_open: bool,
+  in_voice_tag: bool,
+  skipped_voices: usize,
+  wrote_voice: bool,
+}
+
+impl AzureDialect {
+
+  /// Creates an Azure dialect speaking with `voice` (e.g. `en-US-JennyNeural`). The
+  /// language of the document is taken from the start of the voice's name.
+  pub fn new(voice: &str) -> AzureDialect {
+    let lang = voice.splitn(3, '-').take(2).collect::<Vec<&str>>();
+    let lang = if lang.len() == 2 { lang.join("-") } else { "en-US".to_owned() };
+    AzureDialect {
+      voice: voice.to_owned(),
+      lang,
+      open_tags: OpenTags::default(),
+      voice_open: false,
+      in_voice_tag: false,
+      skipped_voices: 0,
+      wrote_voice: false,
+    }
+  }
+
+  /// Opens the default voice, if no voice is open.
+  fn ensure_voice<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    if !self.voice_open {
+      xml_writer.start_element("voice", &[("name", &self.voice)])?;
+      self.voice_open = true;
+      self.wrote_voice = true;
+    }
+    Ok(())
+  }
+
+  /// Closes whichever voice is open.
+  fn close_voice<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    if self.voice_open {
+      xml_writer.end_element("voice")?;
+      self.voice_open = false;
+    }
+    Ok(())
+  }
+
+  fn start_voice<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let name = match start_tag.params.get("name") {
+      Some(name) => name,
+      None => {
+        diagnostics.push(Diagnostic::warning("<voice> is missing \"name\", it was left out.".to_owned(), Some(span)));
+        self.skipped_voices += 1;
+        return Ok(());
+      },
+    };
+    if !self.open_tags.is_empty() || self.in_voice_tag {
+      diagnostics.push(Diagnostic::warning(
+        "Azure can't put a <voice> inside of another tag, it was left out.".to_owned(), Some(span)));
+      self.skipped_voices += 1;
+      return Ok(());
+    }
+
+    self.close_voice(xml_writer)?;
+    xml_writer.start_element("voice", &[("name", name)])?;
+    self.voice_open = true;
+    self.in_voice_tag = true;
+    self.wrote_voice = true;
+    Ok(())
+  }
+
+  fn start_prosody<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let mut attributes = Vec::new();
+    for key in &["pitch", "rate", "volume"] {
+      let value = match start_tag.params.get(*key) {
+        Some(value) => value,
+        None => continue,
+      };
+      match convert_prosody(key, value) {
+        Ok((converted, None)) => attributes.push((*key, converted)),
+        Ok((converted, Some(warning))) => {
+          diagnostics.push(Diagnostic::warning(warning, Some(span)));
+          attributes.push((*key, converted));
+        },
+        Err(warning) => diagnostics.push(Diagnostic::warning(warning, Some(span))),
+      };
+    }
+
+    if attributes.is_empty() {
+      self.open_tags.skip(start_tag);
+      return Ok(());
+    }
+    let attributes = attributes.iter().map(|(key, value)| (*key, value.as_str())).collect::<Vec<(&str, &str)>>();
+    self.open_tags.start(xml_writer, start_tag, "prosody", &attributes)
+  }
+
+}
+
+/// Converts a Polly prosody value into one Azure accepts, along with a warning if it had
+/// to be changed to fit. Values that can't be converted are an error.
+fn convert_prosody(key: &str, value: &str) -> Result<(String, Option<String>), String> {
+  let lowercase = value.trim().to_lowercase();
+  if NAMED_PROSODY.contains(&&*lowercase) {
+    return Ok((lowercase, None));
+  }
+
+  let unconvertible = || format!("Azure doesn't support a prosody {} of \"{}\", it was left out.", key, value);
+  match key {
+    // Polly's rates are a percent of the normal rate, while Azure's are a change in percent.
+    "rate" => {
+      let percent = lowercase.strip_suffix('%').and_then(|percent| percent.parse::<f64>().ok())
+        .filter(|percent| percent.is_finite())
+        .ok_or_else(unconvertible)?;
+      let change = percent - 100.0;
+      let clamped = change.max(RATE_RANGE.0).min(RATE_RANGE.1);
+      let converted = format!("{:+}%", clamped.round() as i64);
+      if clamped != change {
+        Ok((converted.clone(), Some(format!("A prosody rate of {} is outside of what Azure supports, {} was used instead.", value, converted))))
+      } else {
+        Ok((converted, None))
+      }
+    },
+    // Polly's volumes are a change in decibels, while Azure's are a change in percent.
+    "volume" => {
+      let decibels = lowercase.strip_suffix("db").and_then(|decibels| decibels.parse::<f64>().ok())
+        .filter(|decibels| decibels.is_finite())
+        .ok_or_else(unconvertible)?;
+      let change = (10f64.powf(decibels / 20.0) - 1.0) * 100.0;
+      Ok((format!("{:+}%", change.round() as i64), None))
+    },
+    _ => {
+      let is_relative = lowercase.strip_suffix('%')
+        .is_some_and(|percent| percent.starts_with(['+', '-']) && percent[1..].parse::<f64>().is_ok());
+      if is_relative { Ok((lowercase, None)) } else { Err(unconvertible()) }
+    },
+  }
+}
+
+impl Dialect for AzureDialect {
+
+  fn name(&self) -> &'static str {
+    "Azure"
+  }
+
+  fn start_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.start_element("speak", &[
+      ("version", "1.0"),
+      ("xmlns", "http://www.w3.org/2001/10/synthesis"),
+      ("xmlns:mstts", "https://www.w3.org/2001/mstts"),
+      ("xml:lang", &self.lang),
+    ])?;
+    Ok(())
+  }
+
+  fn end_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    if !self.wrote_voice {
+      self.ensure_voice(xml_writer)?;
+    }
+    self.close_voice(xml_writer)?;
+    xml_writer.end_element("speak")?;
+    Ok(())
+  }
+
+  fn start_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let name = start_tag.tag_key.to_lowercase();
+    if name == "voice" {
+      return self.start_voice(xml_writer, start_tag, span, diagnostics);
+    }
+    self.ensure_voice(xml_writer)?;
+    let params = &start_tag.params;
+
+    match &*name {
+      "break" => {
+        let strength = params.get("strength").and_then(|strength| strength.parse::<BreakStrength>().ok());
+        let time = params.get("time").and_then(|time| time.parse::<BreakTime>().ok());
+        xml_writer.ssml_break(strength, time)?;
+      },
+      "mark" => match params.get("name") {
+        Some(mark) => {
+          xml_writer.write_empty_element("bookmark", &[("mark", mark)])?;
+        },
+        None => diagnostics.push(Diagnostic::warning("<mark> is missing \"name\", it was left out.".to_owned(), Some(span))),
+      },
+      "lang" => match params.get("lang") {
+        Some(lang) => self.open_tags.start(xml_writer, start_tag, "lang", &[("xml:lang", lang)])?,
+        None => self.open_tags.leave_out(self.name(), start_tag, span, diagnostics),
+      },
+      "prosody" => self.start_prosody(xml_writer, start_tag, span, diagnostics)?,
+      "express-as" => match params.get("style") {
+        Some(style) => {
+          let mut attributes = vec![("style", style.as_str())];
+          for key in &["styledegree", "role"] {
+            if let Some(value) = params.get(*key) {
+              attributes.push((key, value));
+            }
+          }
+          self.open_tags.start(xml_writer, start_tag, "mstts:express-as", &attributes)?;
+        },
+        None => {
+          diagnostics.push(Diagnostic::warning(
+            "<express-as> is missing \"style\", it was left out.".to_owned(), Some(span)));
+          self.open_tags.skip(start_tag);
+        },
+      },
+      "amazon:effect" => {
+        let whispered = params.get("name").and_then(|effect| effect.parse::<AmazonEffect>().ok()) ==
+          Some(AmazonEffect::Whispered);
+        if whispered {
+          self.open_tags.start(xml_writer, start_tag, "mstts:express-as", &[("style", "whispering")])?;
+        } else {
+          self.open_tags.leave_out(self.name(), start_tag, span, diagnostics);
+        }
+      },
+      "desc" => {
+        diagnostics.push(dropped_tag(self.name(), &start_tag.tag_key, span));
+        self.open_tags.silence(start_tag);
+      },
+      "amazon:breath" => diagnostics.push(dropped_tag(self.name(), &start_tag.tag_key, span)),
+      _ => self.open_tags.start_from_table(xml_writer, TAGS, self.name(), start_tag, span, diagnostics)?,
+    };
+
+    Ok(())
+  }
+
+  fn end_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, end_tag: &EndTag, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if end_tag.tag_key.eq_ignore_ascii_case("voice") {
+      if self.skipped_voices > 0 {
+        self.skipped_voices -= 1;
+      } else if self.in_voice_tag {
+        // Tags opened inside of the voice can outlive it, so they're closed with it, and
+        // opened again in the default voice.
+        self.in_voice_tag = false;
+        let open_tags = self.open_tags.close_all(xml_writer)?;
+        self.close_voice(xml_writer)?;
+        if !open_tags.is_empty() {
+          self.ensure_voice(xml_writer)?;
+          self.open_tags.reopen(xml_writer, open_tags)?;
+        }
+      }
+      return Ok(());
+    }
+    self.open_tags.close(xml_writer, &end_tag.tag_key)
+  }
+
+  fn text<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, text: &str, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if self.open_tags.is_silenced() {
+      return Ok(());
+    }
+    // Whitespace between voices can sit directly inside of `<speak>`.
+    if !text.trim().is_empty() {
+      self.ensure_voice(xml_writer)?;
+    }
+    xml_writer.write_text(text)?;
+    Ok(())
+  }
+
+}
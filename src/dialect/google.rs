@@ -0,0 +1,154 @@
+//! The SSML Google Cloud Text-to-Speech supports. The full list of tags can be found:
+//! [HERE](https://cloud.google.com/text-to-speech/docs/ssml).
+
+use failure::Error;
+
+use std::io::Write;
+
+use crate::diagnostics::Diagnostic;
+use crate::parser::{EndTag, Span, StartTag};
+use crate::ssml_constants::*;
+use crate::xml_writer::XmlWriter;
+
+use super::{dropped_tag, Dialect, OpenTags, TagTable};
+
+/// The tags Google supports that are written as is, along with their required, and
+/// optional attributes.
+const TAGS: TagTable = &[
+  ("p", &[], &[]),
+  ("s", &[], &[]),
+  ("sub", &["alias"], &[]),
+  ("phoneme", &["alphabet", "ph"], &[]),
+  ("prosody", &[], &["rate", "pitch", "volume"]),
+  ("say-as", &["interpret-as"], &["format", "detail", "language"]),
+  ("emphasis", &[], &["level"]),
+  ("voice", &[], &["name", "gender", "variant", "language"]),
+  ("par", &[], &[]),
+  ("seq", &[], &[]),
+  ("media", &[], &["xml:id", "begin", "end", "repeatCount", "repeatDur", "soundLevel", "fadeInDur", "fadeOutDur"]),
+  ("audio", &["src"], &["clipBegin", "clipEnd", "speed", "repeatCount", "repeatDur", "soundLevel"]),
+  ("desc", &[], &[]),
+];
+
+/// The `interpret-as` values Polly, and Google name differently. A missing value means
+/// Google has no equivalent.
+const SAY_AS: &[(&str, Option<&str>)] = &[
+  ("number", Some("cardinal")),
+  ("digits", Some("characters")),
+  ("spell-out", Some("verbatim")),
+  ("address", None),
+];
+
+/// Google Cloud Text-to-Speech's SSML. Polly's `amazon:*` tags have no equivalent, so
+/// whispering is approximated with a soft, slow `<prosody>`, and everything else is left
+/// out with a warning. Google's `<emphasis>`, `<voice>`, `<par>`, `<seq>`, `<media>`, and
+/// `<audio>` (with `<desc>`) can be used from the markup as well.
+#[derive(Clone, Debug, Default)]
+pub struct GoogleDialect {
+  open_tags: OpenTags,
+}
+
+impl Dialect for GoogleDialect {
+
+  fn name(&self) -> &'static str {
+    "Google"
+  }
+
+  fn start_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.start_element("speak", &[])?;
+    Ok(())
+  }
+
+  fn end_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.end_element("speak")?;
+    Ok(())
+  }
+
+  fn start_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let name = start_tag.tag_key.to_lowercase();
+    let params = &start_tag.params;
+
+    match &*name {
+      "break" => {
+        let strength = params.get("strength").and_then(|strength| strength.parse::<BreakStrength>().ok());
+        let time = params.get("time").and_then(|time| time.parse::<BreakTime>().ok());
+        xml_writer.ssml_break(strength, time)?;
+      },
+      "mark" => match params.get("name") {
+        Some(mark) => {
+          xml_writer.write_empty_element("mark", &[("name", mark)])?;
+        },
+        None => diagnostics.push(Diagnostic::warning("<mark> is missing \"name\", it was left out.".to_owned(), Some(span))),
+      },
+      "lang" => match params.get("lang") {
+        Some(lang) => self.open_tags.start(xml_writer, start_tag, "lang", &[("xml:lang", lang)])?,
+        None => self.open_tags.leave_out(self.name(), start_tag, span, diagnostics),
+      },
+      "say-as" => {
+        let interpret_as = params.get("interpret-as").map(|value| value.to_lowercase()).unwrap_or_default();
+        match SAY_AS.iter().find(|(polly, _)| *polly == interpret_as) {
+          Some((_, Some(google))) => {
+            let mut attributes = vec![("interpret-as", *google)];
+            for key in &["format", "detail", "language"] {
+              if let Some(value) = params.get(*key) {
+                attributes.push((key, value));
+              }
+            }
+            self.open_tags.start(xml_writer, start_tag, "say-as", &attributes)?;
+          },
+          Some((_, None)) => {
+            diagnostics.push(Diagnostic::warning(
+              format!("Google doesn't support <say-as interpret-as=\"{}\">, it was left out.", interpret_as),
+              Some(span)));
+            self.open_tags.skip(start_tag);
+          },
+          None => self.open_tags.start_from_table(xml_writer, TAGS, self.name(), start_tag, span, diagnostics)?,
+        };
+      },
+      "emphasis" => match params.get("level") {
+        Some(level) if level.parse::<EmphasisLevel>().is_err() => {
+          diagnostics.push(Diagnostic::warning(
+            format!("\"{}\" is not an emphasis level Google supports, the default was used.", level), Some(span)));
+          self.open_tags.start(xml_writer, start_tag, "emphasis", &[])?;
+        },
+        _ => self.open_tags.start_from_table(xml_writer, TAGS, self.name(), start_tag, span, diagnostics)?,
+      },
+      "amazon:effect" => {
+        let whispered = params.get("name").and_then(|effect| effect.parse::<AmazonEffect>().ok()) ==
+          Some(AmazonEffect::Whispered);
+        if whispered {
+          diagnostics.push(Diagnostic::warning(
+            "Google doesn't support whispering, it was written as a soft, slow <prosody> instead.".to_owned(),
+            Some(span)));
+          self.open_tags.start(xml_writer, start_tag, "prosody", &[("volume", "x-soft"), ("rate", "slow")])?;
+        } else {
+          self.open_tags.leave_out(self.name(), start_tag, span, diagnostics);
+        }
+      },
+      "desc" if !self.open_tags.is_open("audio") => {
+        diagnostics.push(Diagnostic::warning(
+          "<desc> can only be used inside of <audio>, it was left out.".to_owned(), Some(span)));
+        self.open_tags.silence(start_tag);
+      },
+      "amazon:breath" => diagnostics.push(dropped_tag(self.name(), &start_tag.tag_key, span)),
+      _ => self.open_tags.start_from_table(xml_writer, TAGS, self.name(), start_tag, span, diagnostics)?,
+    };
+
+    Ok(())
+  }
+
+  fn end_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, end_tag: &EndTag, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    self.open_tags.close(xml_writer, &end_tag.tag_key)
+  }
+
+  fn text<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, text: &str, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if !self.open_tags.is_silenced() {
+      xml_writer.write_text(text)?;
+    }
+    Ok(())
+  }
+
+}
@@ -0,0 +1,229 @@
+//! Dialects of SSML. Every engine supports a slightly different set of tags, so rather
+//! than always writing Polly's SSML the parser hands each tag to a `Dialect`, which
+//! decides how (or if) that engine's SSML can say the same thing. Tags a dialect can't
+//! write are left out (keeping any text inside of them), with a warning explaining what
+//! was changed.
+//!
+//! `PollyDialect` is what `parse_as_ssml`, and `parse_with_options` use. To target
+//! another engine use `parse_with_dialect`:
+//!
+//! ```rust
+//! use text_to_polly_ssml::dialect::GoogleDialect;
+//! use text_to_polly_ssml::parser::{parse_with_dialect, ParseOptions};
+//! let output = parse_with_dialect(
+//!   "${emphasis|level=strong}hey${/emphasis} ${amazon:effect|name=drc}there${/amazon:effect}",
+//!   &ParseOptions::default(),
+//!   GoogleDialect::default(),
+//! ).unwrap();
+//! assert!(output.ssml.ends_with(r#"<speak><emphasis level="strong">hey</emphasis> there</speak>"#));
+//! assert_eq!(output.diagnostics.len(), 1);
+//! ```
+
+use failure::Error;
+
+use std::io::Write;
+
+use crate::diagnostics::Diagnostic;
+use crate::parser::{EndTag, Span, StartTag};
+use crate::xml_writer::XmlWriter;
+
+mod alexa;
+mod azure;
+mod google;
+mod polly;
+mod w3c;
+
+pub use self::alexa::AlexaDialect;
+pub use self::azure::AzureDialect;
+pub use self::google::GoogleDialect;
+pub use self::polly::PollyDialect;
+pub use self::w3c::W3cDialect;
+
+/// Writes the SSML for one engine. The parser calls `start_speak` first, then one of
+/// `start_tag`, `end_tag`, or `text` for each token in order, and finally `end_speak`.
+///
+/// Anything that gets changed, or left out along the way should be reported by pushing a
+/// warning onto `diagnostics`, using the span of the token that caused it.
+pub trait Dialect {
+
+  /// The name of the engine, used in diagnostics (e.g. `Google`).
+  fn name(&self) -> &'static str;
+
+  /// If the SSML should start with the XML header (`<?xml version="1.0"?>`).
+  fn xml_declaration(&self) -> bool {
+    true
+  }
+
+  /// Writes the root `<speak>` tag.
+  fn start_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error>;
+
+  /// Closes the root `<speak>` tag.
+  fn end_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error>;
+
+  /// Writes the SSML for a single start tag.
+  fn start_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error>;
+
+  /// Writes the SSML for a single end tag.
+  fn end_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, end_tag: &EndTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error>;
+
+  /// Writes a run of text.
+  fn text<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, text: &str, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    xml_writer.write_text(text)?;
+    Ok(())
+  }
+
+}
+
+/// The tags a dialect writes as is, along with their required, and optional attributes.
+pub(crate) type TagTable = &'static [(&'static str, &'static [&'static str], &'static [&'static str])];
+
+/// An SSML tag written for a tag in the markup, kept so it can be written again.
+#[derive(Clone, Debug)]
+struct WrittenTag {
+  name: String,
+  attributes: Vec<(String, String)>,
+}
+
+/// A markup tag that's open, along with what was written for it, and if the text inside
+/// of it is left out.
+#[derive(Clone, Debug)]
+struct OpenTag {
+  tag_key: String,
+  written: Option<WrittenTag>,
+  silent: bool,
+}
+
+/// Keeps track of which SSML tag was written for each tag in the markup, so the end tag in
+/// the markup closes exactly what was opened, even when a tag was left out.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct OpenTags {
+  open: Vec<OpenTag>,
+}
+
+impl OpenTags {
+
+  /// Records that the markup tag `tag_key` was written as `written`, if anything.
+  fn push(&mut self, tag_key: &str, written: Option<WrittenTag>) {
+    self.open.push(OpenTag { tag_key: tag_key.to_lowercase(), written, silent: false });
+  }
+
+  /// Writes `name` as the SSML for the markup tag `start_tag`.
+  pub(crate) fn start<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, name: &str,
+    attributes: &[(&str, &str)]) -> Result<(), Error> {
+    xml_writer.start_element(name, attributes)?;
+    self.push(&start_tag.tag_key, Some(WrittenTag {
+      name: name.to_owned(),
+      attributes: attributes.iter().map(|(key, value)| ((*key).to_owned(), (*value).to_owned())).collect(),
+    }));
+    Ok(())
+  }
+
+  /// Leaves out the markup tag `start_tag`, but keeps track of it so it's end tag is left
+  /// out as well.
+  pub(crate) fn skip(&mut self, start_tag: &StartTag) {
+    self.push(&start_tag.tag_key, None);
+  }
+
+  /// Leaves out the markup tag `start_tag` with a warning that `dialect` doesn't support
+  /// it.
+  pub(crate) fn leave_out(&mut self, dialect: &str, start_tag: &StartTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) {
+    diagnostics.push(dropped_tag(dialect, &start_tag.tag_key, span));
+    self.skip(start_tag);
+  }
+
+  /// Leaves out the markup tag `start_tag` along with the text inside of it, such as a
+  /// `desc` that would otherwise be read out loud.
+  pub(crate) fn silence(&mut self, start_tag: &StartTag) {
+    self.open.push(OpenTag { tag_key: start_tag.tag_key.to_lowercase(), written: None, silent: true });
+  }
+
+  /// If text should be left out, because it's inside of a tag left out with `silence`.
+  pub(crate) fn is_silenced(&self) -> bool {
+    self.open.iter().any(|open| open.silent)
+  }
+
+  /// Writes a tag from `tags` as is, or leaves it out if it isn't in `tags`, or it's
+  /// missing a required attribute.
+  pub(crate) fn start_from_table<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, tags: TagTable, dialect: &str,
+    start_tag: &StartTag, span: Span, diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let name = start_tag.tag_key.to_lowercase();
+    let (required, optional) = match tags.iter().find(|(tag, _, _)| *tag == name) {
+      Some(&(_, required, optional)) => (required, optional),
+      None => {
+        self.leave_out(dialect, start_tag, span, diagnostics);
+        return Ok(());
+      },
+    };
+    if let Some(missing) = required.iter().find(|key| !start_tag.params.contains_key(**key)) {
+      diagnostics.push(Diagnostic::warning(
+        format!("<{}> is missing \"{}\", it was left out.", name, missing), Some(span)));
+      self.skip(start_tag);
+      return Ok(());
+    }
+
+    let attributes = required.iter().chain(optional.iter())
+      .filter_map(|key| start_tag.params.get(*key).map(|value| (*key, value.as_str())))
+      .collect::<Vec<(&str, &str)>>();
+    self.start(xml_writer, start_tag, &name, &attributes)
+  }
+
+  /// Closes the most recent markup tag named `tag_key`, writing an end tag for everything
+  /// that was written for it.
+  pub(crate) fn close<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, tag_key: &str) -> Result<(), Error> {
+    let tag_key = tag_key.to_lowercase();
+    if let Some(position) = self.open.iter().rposition(|open| open.tag_key == tag_key) {
+      if let Some(written) = self.open.remove(position).written {
+        xml_writer.end_element(&written.name)?;
+      }
+    }
+    Ok(())
+  }
+
+  /// Closes every open markup tag, handing them back so they can be opened again with
+  /// `reopen`.
+  pub(crate) fn close_all<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<OpenTags, Error> {
+    for open in self.open.iter().rev() {
+      if let Some(ref written) = open.written {
+        xml_writer.end_element(&written.name)?;
+      }
+    }
+    Ok(OpenTags {
+      open: std::mem::take(&mut self.open),
+    })
+  }
+
+  /// Writes everything `close_all` closed again, so their end tags in the markup still
+  /// close them.
+  pub(crate) fn reopen<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, closed: OpenTags) -> Result<(), Error> {
+    for open in closed.open {
+      if let Some(ref written) = open.written {
+        let attributes = written.attributes.iter()
+          .map(|(key, value)| (key.as_str(), value.as_str()))
+          .collect::<Vec<(&str, &str)>>();
+        xml_writer.start_element(&written.name, &attributes)?;
+      }
+      self.open.push(open);
+    }
+    Ok(())
+  }
+
+  /// If a markup tag named `tag_key` is open.
+  pub(crate) fn is_open(&self, tag_key: &str) -> bool {
+    self.open.iter().any(|open| open.tag_key.eq_ignore_ascii_case(tag_key))
+  }
+
+  /// If no markup tags are open.
+  pub(crate) fn is_empty(&self) -> bool {
+    self.open.is_empty()
+  }
+
+}
+
+/// The warning for a tag a dialect left out, keeping the text inside of it.
+pub(crate) fn dropped_tag(dialect: &str, tag_key: &str, span: Span) -> Diagnostic {
+  Diagnostic::warning(format!("{} doesn't support <{}>, it was left out.", dialect, tag_key), Some(span))
+}
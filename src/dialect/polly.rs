@@ -0,0 +1,130 @@
+//! The SSML Amazon Polly supports, which is what the rest of this crate writes.
+
+use failure::Error;
+
+use std::io::Write;
+
+use crate::diagnostics::Diagnostic;
+use crate::parser::{write_end_tag, write_start_tag, EndTag, Span, StartTag};
+use crate::ssml_constants::*;
+use crate::xml_writer::XmlWriter;
+
+use super::{dropped_tag, Dialect, OpenTags};
+
+/// Amazon Polly's SSML. Anything that isn't valid (an unknown tag, a missing parameter,
+/// etc.) is silently skipped, exactly like `parse_as_ssml`.
+///
+/// Polly can't play `<audio>`, or switch `<voice>`, so they're left out with a warning,
+/// keeping any text inside of them (`voice::split_by_voice` splits a document up by voice
+/// instead). An audio clip's `<desc>` is left out along with it's text. Only Polly's standard voices support `<emphasis>`, so for any other engine
+/// emphasis is written as a louder, or softer `<prosody>` instead, with a warning.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::dialect::PollyDialect;
+/// use text_to_polly_ssml::parser::{parse_with_dialect, ParseOptions};
+/// use text_to_polly_ssml::ssml_constants::PollyEngine;
+/// let output = parse_with_dialect(
+///   "${emphasis|level=strong}Stop${/emphasis} right there.",
+///   &ParseOptions::default(),
+///   PollyDialect::new(PollyEngine::Neural),
+/// ).unwrap();
+/// assert!(output.ssml.ends_with(r#"><prosody volume="+6dB" rate="slow">Stop</prosody> right there.</speak>"#));
+/// assert_eq!(output.diagnostics.len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PollyDialect {
+  engine: PollyEngine,
+  open_tags: OpenTags,
+}
+
+impl PollyDialect {
+
+  /// Creates a Polly dialect for voices using `engine`.
+  pub fn new(engine: PollyEngine) -> PollyDialect {
+    PollyDialect {
+      engine,
+      open_tags: OpenTags::default(),
+    }
+  }
+
+  /// Writes `<emphasis>` as the closest `<prosody>` for engines that don't support it, or
+  /// leaves it out entirely for no emphasis.
+  fn start_emphasis_as_prosody<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag,
+    span: Span, diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let level = start_tag.params.get("level").and_then(|level| level.parse::<EmphasisLevel>().ok())
+      .unwrap_or(EmphasisLevel::Moderate);
+    let (volume, rate) = match level.as_prosody() {
+      Some(prosody) => prosody,
+      None => {
+        self.open_tags.skip(start_tag);
+        return Ok(());
+      },
+    };
+
+    diagnostics.push(Diagnostic::warning(
+      format!("Polly's {} voices don't support <emphasis>, it was written as <prosody> instead.", self.engine),
+      Some(span)));
+    let rate = rate.map(|rate| rate.to_string());
+    let mut attributes = vec![("volume", volume)];
+    if let Some(ref rate) = rate {
+      attributes.push(("rate", rate));
+    }
+    self.open_tags.start(xml_writer, start_tag, "prosody", &attributes)
+  }
+
+}
+
+impl Dialect for PollyDialect {
+
+  fn name(&self) -> &'static str {
+    "Polly"
+  }
+
+  fn start_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.start_ssml_speak(None, None)?;
+    Ok(())
+  }
+
+  fn end_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.end_ssml_speak()?;
+    Ok(())
+  }
+
+  fn start_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if !self.engine.supports_emphasis() && start_tag.tag_key.eq_ignore_ascii_case("emphasis") {
+      return self.start_emphasis_as_prosody(xml_writer, start_tag, span, diagnostics);
+    }
+    if start_tag.tag_key.eq_ignore_ascii_case("desc") {
+      diagnostics.push(dropped_tag(self.name(), &start_tag.tag_key, span));
+      self.open_tags.silence(start_tag);
+      return Ok(());
+    }
+    if start_tag.tag_key.eq_ignore_ascii_case("audio") || start_tag.tag_key.eq_ignore_ascii_case("voice") {
+      diagnostics.push(dropped_tag(self.name(), &start_tag.tag_key, span));
+    }
+    write_start_tag(xml_writer, start_tag);
+    Ok(())
+  }
+
+  fn end_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, end_tag: &EndTag, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let emphasis = !self.engine.supports_emphasis() && end_tag.tag_key.eq_ignore_ascii_case("emphasis");
+    if emphasis || end_tag.tag_key.eq_ignore_ascii_case("desc") {
+      return self.open_tags.close(xml_writer, &end_tag.tag_key);
+    }
+    write_end_tag(xml_writer, end_tag);
+    Ok(())
+  }
+
+  fn text<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, text: &str, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if !self.open_tags.is_silenced() {
+      xml_writer.write_text(text)?;
+    }
+    Ok(())
+  }
+
+}
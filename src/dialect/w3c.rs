@@ -0,0 +1,224 @@
+//! Plain W3C SSML 1.1, without any vendor's extensions. Anything written in this dialect
+//! should work with any engine that follows the standard, which can be found:
+//! [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/).
+
+use failure::Error;
+
+use std::io::Write;
+
+use crate::diagnostics::Diagnostic;
+use crate::parser::{EndTag, Span, StartTag};
+use crate::ssml_constants::*;
+use crate::xml_writer::XmlWriter;
+
+use super::{dropped_tag, Dialect, OpenTags, TagTable};
+
+/// The tags SSML 1.1 defines that are written as is, along with their required, and
+/// optional attributes.
+const TAGS: TagTable = &[
+  ("p", &[], &[]),
+  ("s", &[], &[]),
+  ("token", &[], &[]),
+  ("sub", &["alias"], &[]),
+  ("phoneme", &["ph"], &["alphabet"]),
+  ("say-as", &["interpret-as"], &["format", "detail"]),
+  ("prosody", &[], &["pitch", "contour", "range", "rate", "duration", "volume"]),
+  ("emphasis", &[], &["level"]),
+  ("voice", &[], &["gender", "age", "variant", "name", "languages", "required", "ordering", "onvoicefailure"]),
+  ("audio", &["src"], &[
+    "fetchtimeout", "fetchhint", "maxage", "maxstale", "clipBegin", "clipEnd", "repeatDur", "repeatCount",
+    "soundLevel", "speed",
+  ]),
+];
+
+/// W3C SSML 1.1. `<emphasis>`, `<voice>`, `<audio>` (with `<desc>`), `<lexicon>`,
+/// `<meta>`, and `<metadata>` can all be used from the markup. Polly's `amazon:*` tags
+/// are left out, keeping any text inside of them, and `<w>` is written without Polly's
+/// roles.
+///
+/// The standard requires `<lexicon>`, `<meta>`, and `<metadata>` to come before anything
+/// else, so ones that don't are left out with a warning.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::dialect::W3cDialect;
+/// use text_to_polly_ssml::parser::{parse_with_dialect, ParseOptions};
+/// let output = parse_with_dialect(
+///   "${lexicon|uri=https://example.com/names.pls}${amazon:effect|name=drc}Loud${/amazon:effect}",
+///   &ParseOptions::default(),
+///   W3cDialect::new("en-GB"),
+/// ).unwrap();
+/// assert_eq!(output.ssml, concat!(
+///   r#"<?xml version="1.0"?><speak version="1.1" xmlns="http://www.w3.org/2001/10/synthesis" xml:lang="en-GB">"#,
+///   r#"<lexicon uri="https://example.com/names.pls"/>Loud</speak>"#,
+/// ));
+/// assert_eq!(output.diagnostics.len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct W3cDialect {
+  lang: String,
+  open_tags: OpenTags,
+  wrote_content: bool,
+  in_audio: usize,
+}
+
+impl W3cDialect {
+
+  /// Creates a W3C dialect for a document in `lang` (e.g. `en-US`).
+  pub fn new(lang: &str) -> W3cDialect {
+    W3cDialect {
+      lang: lang.to_owned(),
+      open_tags: OpenTags::default(),
+      wrote_content: false,
+      in_audio: 0,
+    }
+  }
+
+  /// Writes one of the tags that has to come before anything else.
+  fn start_head_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, name: &str,
+    span: Span, diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if self.wrote_content {
+      diagnostics.push(Diagnostic::warning(
+        format!("<{}> has to come before anything else, it was left out.", name), Some(span)));
+      if name == "metadata" {
+        self.open_tags.skip(start_tag);
+      }
+      return Ok(());
+    }
+
+    let params = &start_tag.params;
+    match name {
+      "lexicon" => match params.get("uri") {
+        Some(uri) => {
+          let mut attributes = vec![("uri", uri.as_str())];
+          for key in &["xml:id", "type", "fetchtimeout", "fetchhint", "maxage", "maxstale"] {
+            if let Some(value) = params.get(*key) {
+              attributes.push((key, value));
+            }
+          }
+          xml_writer.write_empty_element("lexicon", &attributes)?;
+        },
+        None => diagnostics.push(Diagnostic::warning("<lexicon> is missing \"uri\", it was left out.".to_owned(), Some(span))),
+      },
+      "meta" => {
+        let key = ["name", "http-equiv"].iter().find_map(|key| params.get(*key).map(|value| (*key, value.as_str())));
+        match (key, params.get("content")) {
+          (Some(key), Some(content)) => {
+            xml_writer.write_empty_element("meta", &[key, ("content", content)])?;
+          },
+          _ => diagnostics.push(Diagnostic::warning(
+            "<meta> needs a \"name\", or \"http-equiv\", and a \"content\", it was left out.".to_owned(), Some(span))),
+        };
+      },
+      _ => self.open_tags.start(xml_writer, start_tag, "metadata", &[])?,
+    };
+    Ok(())
+  }
+
+}
+
+impl Default for W3cDialect {
+  fn default() -> W3cDialect {
+    W3cDialect::new("en-US")
+  }
+}
+
+impl Dialect for W3cDialect {
+
+  fn name(&self) -> &'static str {
+    "SSML 1.1"
+  }
+
+  fn start_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.start_element("speak", &[
+      ("version", "1.1"),
+      ("xmlns", "http://www.w3.org/2001/10/synthesis"),
+      ("xml:lang", &self.lang),
+    ])?;
+    Ok(())
+  }
+
+  fn end_speak<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.end_element("speak")?;
+    Ok(())
+  }
+
+  fn start_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, start_tag: &StartTag, span: Span,
+    diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let name = start_tag.tag_key.to_lowercase();
+    let params = &start_tag.params;
+    if let "lexicon" | "meta" | "metadata" = &*name {
+      return self.start_head_tag(xml_writer, start_tag, &name, span, diagnostics);
+    }
+    self.wrote_content = true;
+
+    match &*name {
+      "break" => {
+        let strength = params.get("strength").and_then(|strength| strength.parse::<BreakStrength>().ok());
+        let time = params.get("time").and_then(|time| time.parse::<BreakTime>().ok());
+        xml_writer.ssml_break(strength, time)?;
+      },
+      "mark" => match params.get("name") {
+        Some(mark) => {
+          xml_writer.write_empty_element("mark", &[("name", mark)])?;
+        },
+        None => diagnostics.push(Diagnostic::warning("<mark> is missing \"name\", it was left out.".to_owned(), Some(span))),
+      },
+      "lang" => match params.get("lang") {
+        Some(lang) => {
+          let onlangfailure = params.get("onlangfailure").map_or("processorchoice", String::as_str);
+          let attributes = [("xml:lang", lang.as_str()), ("onlangfailure", onlangfailure)];
+          self.open_tags.start(xml_writer, start_tag, "lang", &attributes)?;
+        },
+        None => self.open_tags.leave_out(self.name(), start_tag, span, diagnostics),
+      },
+      "w" => {
+        if params.contains_key("role") {
+          diagnostics.push(Diagnostic::warning(
+            "SSML 1.1 doesn't define Polly's word roles, the role was left out.".to_owned(), Some(span)));
+        }
+        self.open_tags.start(xml_writer, start_tag, "w", &[])?;
+      },
+      "desc" => {
+        if self.in_audio == 0 {
+          diagnostics.push(Diagnostic::warning(
+            "<desc> can only be used inside of <audio>, it was left out.".to_owned(), Some(span)));
+          self.open_tags.silence(start_tag);
+        } else {
+          let attributes = params.get("lang").map(|lang| vec![("xml:lang", lang.as_str())]).unwrap_or_default();
+          self.open_tags.start(xml_writer, start_tag, "desc", &attributes)?;
+        }
+      },
+      "audio" => {
+        self.in_audio += 1;
+        self.open_tags.start_from_table(xml_writer, TAGS, self.name(), start_tag, span, diagnostics)?;
+      },
+      "amazon:breath" => diagnostics.push(dropped_tag(self.name(), &start_tag.tag_key, span)),
+      _ => self.open_tags.start_from_table(xml_writer, TAGS, self.name(), start_tag, span, diagnostics)?,
+    };
+
+    Ok(())
+  }
+
+  fn end_tag<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, end_tag: &EndTag, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if end_tag.tag_key.eq_ignore_ascii_case("audio") {
+      self.in_audio = self.in_audio.saturating_sub(1);
+    }
+    self.open_tags.close(xml_writer, &end_tag.tag_key)
+  }
+
+  fn text<W: Write>(&mut self, xml_writer: &mut XmlWriter<W>, text: &str, _span: Span,
+    _diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if self.open_tags.is_silenced() {
+      return Ok(());
+    }
+    if !text.trim().is_empty() && !self.open_tags.is_open("metadata") {
+      self.wrote_content = true;
+    }
+    xml_writer.write_text(text)?;
+    Ok(())
+  }
+
+}
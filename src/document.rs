@@ -0,0 +1,267 @@
+//! A tree representation of the `${...}` markup. Where the parser hands back a flat stream
+//! of tokens, this nests everything inside of the tag that contains it, which makes it
+//! a lot easier to store, inspect, or build up from structured data (e.g. JSON from a
+//! web UI) instead of markup.
+//!
+//! With the `serde` feature enabled every type here can be (de)serialized, and with the
+//! `json` feature enabled documents can be loaded straight from JSON, and rendered to SSML.
+
+use failure::Error;
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use crate::parser::{break_time, tokenize_with_spans, write_end_tag, write_start_tag, EndTag, Span, StartTag};
+use crate::ssml_constants::*;
+use crate::xml_writer::XmlWriter;
+
+/// A parsed document. The top level nodes are everything that would end up directly
+/// inside of the `<speak>` tag.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Document {
+  /// The top level nodes of the document.
+  pub nodes: Vec<Node>,
+}
+
+/// A single node inside of a document, either a tag or some text.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "lowercase"))]
+pub enum Node {
+  /// A tag, such as `${prosody|rate=fast}...${/prosody}`.
+  Element(Element),
+  /// A run of text.
+  Text(Text),
+}
+
+/// A tag, along with everything inside of it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Element {
+  /// The name of the tag, e.g. `prosody`.
+  pub name: String,
+  /// The parameters of the tag, e.g. `rate=fast`.
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub attributes: BTreeMap<String, String>,
+  /// Everything inside of the tag.
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub children: Vec<Node>,
+  /// If the tag was closed in the markup. Tags that were never closed are rendered
+  /// the same way the parser renders them, without a closing tag.
+  #[cfg_attr(feature = "serde", serde(default = "default_closed"))]
+  pub closed: bool,
+  /// Where in the markup this tag came from, if it came from markup at all.
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub span: Option<Span>,
+}
+
+/// A run of text.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Text {
+  /// The text itself, with any escaped tag openings (`$\{`) already unescaped.
+  pub text: String,
+  /// Where in the markup this text came from, if it came from markup at all.
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub span: Option<Span>,
+}
+
+#[cfg(feature = "serde")]
+fn default_closed() -> bool {
+  true
+}
+
+impl Element {
+
+  /// Creates a new closed element, with no children.
+  pub fn new(name: &str, attributes: BTreeMap<String, String>) -> Element {
+    Element {
+      name: name.to_owned(),
+      attributes,
+      children: Vec::new(),
+      closed: true,
+      span: None,
+    }
+  }
+
+  /// If this element can never contain anything, e.g. `break`.
+  pub fn is_empty_tag(&self) -> bool {
+    is_empty_tag(&self.name)
+  }
+
+}
+
+impl Node {
+
+  /// Creates a new text node.
+  pub fn text(text: &str) -> Node {
+    Node::Text(Text {
+      text: text.to_owned(),
+      span: None,
+    })
+  }
+
+  /// Sets the span of this node, handy when comparing against parsed markup.
+  pub fn with_span(mut self, start: usize, end: usize) -> Node {
+    let span = Some(Span { start, end });
+    match self {
+      Node::Element(ref mut element) => element.span = span,
+      Node::Text(ref mut text) => text.span = span,
+    };
+    self
+  }
+
+}
+
+impl Document {
+
+  /// Parses some markup into a document. Just like the parser no validation of tags
+  /// happens here. A closing tag that doesn't match any open tag is dropped, and any
+  /// tag that is never closed is marked as such.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::document::{Document, Node};
+  /// let document = Document::from_markup("${p}hey${/p}").unwrap();
+  /// match document.nodes[0] {
+  ///   Node::Element(ref element) => {
+  ///     assert_eq!(element.name, "p");
+  ///     assert_eq!(element.children, vec![Node::text("hey").with_span(4, 7)]);
+  ///   },
+  ///   _ => panic!("Expected an element!"),
+  /// };
+  /// ```
+  pub fn from_markup(data: &str) -> Result<Document, Error> {
+    let mut root = Vec::new();
+    let mut open: Vec<Element> = Vec::new();
+
+    for (span, item) in tokenize_with_spans(data)? {
+      if let Some(start_tag) = item.start_tag {
+        let mut element = Element::new(&start_tag.tag_key, start_tag.params);
+        element.span = Some(span);
+        if element.is_empty_tag() {
+          push_node(&mut root, &mut open, Node::Element(element));
+        } else {
+          element.closed = false;
+          open.push(element);
+        }
+      }
+
+      if let Some(end_tag) = item.end_tag {
+        let name = end_tag.tag_key.to_lowercase();
+        if let Some(position) = open.iter().rposition(|element| element.name.to_lowercase() == name) {
+          while open.len() > position + 1 {
+            let unclosed = open.pop().unwrap();
+            push_node(&mut root, &mut open, Node::Element(unclosed));
+          }
+          let mut element = open.pop().unwrap();
+          element.closed = true;
+          element.span = element.span.map(|start| Span { start: start.start, end: span.end });
+          push_node(&mut root, &mut open, Node::Element(element));
+        }
+      }
+
+      if let Some(text) = item.data {
+        push_node(&mut root, &mut open, Node::Text(Text {
+          text: text.replace("$\\{", "${"),
+          span: Some(span),
+        }));
+      }
+    }
+
+    while let Some(unclosed) = open.pop() {
+      push_node(&mut root, &mut open, Node::Element(unclosed));
+    }
+
+    Ok(Document {
+      nodes: root,
+    })
+  }
+
+  /// Renders the document out to a String of SSML. Breaks longer than Polly allows are cut
+  /// down to `BreakTime::POLLY_MAX`, the same as the parser does.
+  pub fn render(&self) -> Result<String, Error> {
+    let mut xml_writer = XmlWriter::new()?;
+    self.write_to(&mut xml_writer)?;
+    xml_writer.into_string()
+  }
+
+  /// Writes the document into an existing XML Writer, wrapped in a `<speak>` tag, the same
+  /// as `render`.
+  pub fn write_to<W: Write>(&self, xml_writer: &mut XmlWriter<W>) -> Result<(), Error> {
+    xml_writer.start_ssml_speak(None, None)?;
+    write_nodes(xml_writer, &self.nodes)?;
+    xml_writer.end_ssml_speak()?;
+    Ok(())
+  }
+
+  /// Loads a document from JSON.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::document::Document;
+  /// let document = Document::from_json(r#"{"nodes": [
+  ///   {"type": "element", "name": "sub", "attributes": {"alias": "mercury"},
+  ///     "children": [{"type": "text", "text": "hg"}]}
+  /// ]}"#).unwrap();
+  /// assert!(document.render().unwrap().ends_with(r#"<sub alias="mercury">hg</sub></speak>"#));
+  /// ```
+  #[cfg(feature = "json")]
+  pub fn from_json(json: &str) -> Result<Document, Error> {
+    Ok(serde_json::from_str(json)?)
+  }
+
+  /// Turns the document into JSON.
+  #[cfg(feature = "json")]
+  pub fn to_json(&self) -> Result<String, Error> {
+    Ok(serde_json::to_string(self)?)
+  }
+
+}
+
+/// Loads a document from JSON, and renders it straight to SSML.
+#[cfg(feature = "json")]
+pub fn render_json(json: &str) -> Result<String, Error> {
+  Document::from_json(json)?.render()
+}
+
+/// If a tag can never contain anything, and as such never has a closing tag.
+pub(crate) fn is_empty_tag(name: &str) -> bool {
+  matches!(name.parse::<PossibleOpenTags>(),
+    Ok(PossibleOpenTags::Break) | Ok(PossibleOpenTags::AmazonBreath))
+}
+
+fn push_node(root: &mut Vec<Node>, open: &mut [Element], node: Node) {
+  match open.last_mut() {
+    Some(parent) => parent.children.push(node),
+    None => root.push(node),
+  };
+}
+
+fn write_nodes<W: Write>(xml_writer: &mut XmlWriter<W>, nodes: &[Node]) -> Result<(), Error> {
+  for node in nodes {
+    match *node {
+      Node::Text(ref text) => { xml_writer.write_text(&text.text)?; },
+      Node::Element(ref element) => {
+        let mut start_tag = StartTag {
+          tag_key: element.name.clone(),
+          params: element.attributes.clone(),
+        };
+        if break_time(&start_tag).is_some_and(|time| time > BreakTime::POLLY_MAX) {
+          start_tag.params.insert("time".to_owned(), BreakTime::POLLY_MAX.to_string());
+        }
+        write_start_tag(xml_writer, &start_tag);
+        write_nodes(xml_writer, &element.children)?;
+        if element.closed && !element.is_empty_tag() {
+          write_end_tag(xml_writer, &EndTag {
+            tag_key: element.name.clone(),
+          });
+        }
+      },
+    };
+  }
+  Ok(())
+}
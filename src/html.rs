@@ -0,0 +1,348 @@
+//! Converts HTML into SSML, so pages (like blog posts) can be read out loud without
+//! stripping the HTML by hand first. This needs the `html` feature.
+//!
+//! The DOM is walked, and every element is written according to it's `ElementMapping`.
+//! Out of the box:
+//!
+//!   * `<p>`, `<div>`, `<section>`, and friends are paragraphs.
+//!   * `<h1>` through `<h6>` are headings, and `<li>`, `<dt>`, and `<dd>` are list items.
+//!   * `<em>`, and `<i>` are moderate emphasis, while `<strong>`, and `<b>` are strong
+//!     emphasis.
+//!   * `<abbr title="...">` is read as it's title, using `<sub>`.
+//!   * `<br>` is a weak break, and `<hr>` an extra strong one.
+//!   * Scripts, styles, navigation, the `<head>`, and preformatted text are left out.
+//!
+//! Anything else is read as the text inside of it. Text in HTML is read as is, so `${...}`
+//! isn't treated as markup.
+
+use failure::Error;
+use scraper::{ElementRef, Html, Node};
+
+use std::collections::BTreeMap;
+
+use crate::conversion::Tokens;
+use crate::dialect::Dialect;
+use crate::parser::{run_passes, write_tokens, ParseOptions, ParseOutput, Span};
+use crate::ssml_constants::*;
+
+/// How an element gets written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ElementMapping {
+  /// Left out, along with everything inside of it.
+  Drop,
+  /// Read as the text inside of it.
+  Text,
+  /// A paragraph (`<p>`).
+  Paragraph,
+  /// A sentence (`<s>`).
+  Sentence,
+  /// A heading, read louder, and slower in a paragraph of it's own after a strong break.
+  Heading,
+  /// A sentence, followed by a medium break.
+  ListItem,
+  /// Emphasised at the given level (`<emphasis>`).
+  Emphasis(EmphasisLevel),
+  /// Read as the element's `title` attribute (`<sub alias="...">`), if it has one.
+  SubFromTitle,
+  /// A break, leaving out anything inside of it.
+  Break(BreakStrength),
+}
+
+/// Options for controlling how HTML gets converted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HtmlOptions {
+  /// How each element is written, by it's (lowercase) name. Elements that aren't in here
+  /// are read as the text inside of them.
+  pub mappings: BTreeMap<String, ElementMapping>,
+}
+
+impl HtmlOptions {
+
+  /// Sets how an element is written, replacing any mapping it already had.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::html::{ElementMapping, HtmlOptions};
+  /// let mut options = HtmlOptions::default();
+  /// options.insert("aside", ElementMapping::Drop);
+  /// assert_eq!(options.mapping("ASIDE"), ElementMapping::Drop);
+  /// assert_eq!(options.mapping("span"), ElementMapping::Text);
+  /// ```
+  pub fn insert(&mut self, element: &str, mapping: ElementMapping) {
+    self.mappings.insert(element.to_ascii_lowercase(), mapping);
+  }
+
+  /// How an element is written.
+  pub fn mapping(&self, element: &str) -> ElementMapping {
+    self.mappings.get(&element.to_ascii_lowercase()).copied().unwrap_or(ElementMapping::Text)
+  }
+
+}
+
+impl Default for HtmlOptions {
+  fn default() -> HtmlOptions {
+    let mut options = HtmlOptions {
+      mappings: BTreeMap::new(),
+    };
+    for element in &["head", "script", "style", "noscript", "template", "nav", "pre", "svg"] {
+      options.insert(element, ElementMapping::Drop);
+    }
+    for element in &["p", "div", "section", "article", "main", "blockquote", "figcaption", "td", "th"] {
+      options.insert(element, ElementMapping::Paragraph);
+    }
+    for element in &["h1", "h2", "h3", "h4", "h5", "h6"] {
+      options.insert(element, ElementMapping::Heading);
+    }
+    for element in &["li", "dt", "dd"] {
+      options.insert(element, ElementMapping::ListItem);
+    }
+    for element in &["em", "i"] {
+      options.insert(element, ElementMapping::Emphasis(EmphasisLevel::Moderate));
+    }
+    for element in &["strong", "b"] {
+      options.insert(element, ElementMapping::Emphasis(EmphasisLevel::Strong));
+    }
+    options.insert("abbr", ElementMapping::SubFromTitle);
+    options.insert("acronym", ElementMapping::SubFromTitle);
+    options.insert("br", ElementMapping::Break(BreakStrength::Weak));
+    options.insert("hr", ElementMapping::Break(BreakStrength::XStrong));
+    options
+  }
+}
+
+/// Converts `html` into SSML for `dialect`. The text is run through the same passes as
+/// markup parsed with `options` (abbreviations, the lexicon, etc.), and diagnostics point
+/// at the start tag of the element they came from, as best as it can be found.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::dialect::PollyDialect;
+/// use text_to_polly_ssml::html::{html_to_ssml, HtmlOptions};
+/// use text_to_polly_ssml::parser::ParseOptions;
+/// let output = html_to_ssml(
+///   r#"<h1>Hello</h1><script>track()</script><p>Meet the <abbr title="World Wide Web Consortium">W3C</abbr>.</p>"#,
+///   &HtmlOptions::default(),
+///   &ParseOptions::default(),
+///   PollyDialect::default(),
+/// ).unwrap();
+/// assert!(output.ssml.ends_with(concat!(
+///   r#"><break strength="strong"/><p><prosody volume="loud" rate="slow">Hello</prosody></p>"#,
+///   r#"<p>Meet the <sub alias="World Wide Web Consortium">W3C</sub>.</p></speak>"#,
+/// )));
+/// ```
+pub fn html_to_ssml<D: Dialect>(html: &str, html_options: &HtmlOptions, options: &ParseOptions,
+  dialect: D) -> Result<ParseOutput, Error> {
+  let document = Html::parse_document(html);
+  let mut converter = Converter {
+    options: html_options,
+    source: html.to_ascii_lowercase(),
+    cursor: 0,
+    tokens: Tokens::new(),
+    space_before: true,
+    blocks: Vec::new(),
+    open_block: None,
+    inline: 0,
+  };
+  converter.element(document.root_element());
+  converter.end_block();
+
+  let mut diagnostics = Vec::new();
+  let parsed = run_passes(converter.tokens.items, options, &mut diagnostics)?;
+  let ssml = write_tokens(&parsed, options, dialect, &mut diagnostics)?;
+  Ok(ParseOutput {
+    ssml,
+    diagnostics,
+  })
+}
+
+/// Keeps track of where we are in the DOM while converting it.
+struct Converter<'a> {
+  options: &'a HtmlOptions,
+  /// The HTML in lowercase, for finding where elements start.
+  source: String,
+  /// How far into the HTML elements have been found.
+  cursor: usize,
+  /// Text is collected with it's whitespace collapsed, and written as is.
+  tokens: Tokens,
+  /// If the last thing written was whitespace, or a block, so more whitespace is dropped.
+  space_before: bool,
+  /// Every paragraph, sentence, etc. we're inside of.
+  blocks: Vec<(Span, ElementMapping)>,
+  /// The block that's been written, and not closed yet. Blocks can't be nested, so they
+  /// only get opened once there's something to put in them, and are closed as soon as
+  /// another block starts, or ends.
+  open_block: Option<(Span, ElementMapping)>,
+  /// How many emphasis, etc. tags are open. Blocks inside of them are read as text.
+  inline: usize,
+}
+
+impl<'a> Converter<'a> {
+
+  /// Finds where the start tag for `name` is in the HTML, after the last one found. Tags
+  /// the HTML parser made up, and don't appear in the HTML get an empty span.
+  fn locate(&mut self, name: &str) -> Span {
+    let needle = format!("<{}", name);
+    let mut from = self.cursor;
+    while let Some(found) = self.source[from..].find(&needle) {
+      let start = from + found;
+      let after = start + needle.len();
+      if self.source[after..].starts_with(|character: char| character.is_whitespace() || character == '>' || character == '/') {
+        let end = self.source[after..].find('>').map(|end| after + end + 1).unwrap_or(self.source.len());
+        self.cursor = end;
+        return Span { start, end };
+      }
+      from = after;
+    }
+    Span { start: self.cursor, end: self.cursor }
+  }
+
+  fn start(&mut self, span: Span, tag_key: &str, params: &[(&str, &str)]) {
+    self.tokens.flush_text();
+    self.tokens.start(span, tag_key, params);
+  }
+
+  fn end(&mut self, span: Span, tag_key: &str) {
+    self.tokens.flush_text();
+    self.tokens.end(span, tag_key);
+  }
+
+  /// Adds text, collapsing whitespace the same way a browser would.
+  fn push_text(&mut self, span: Span, text: &str) {
+    let mut collapsed = String::with_capacity(text.len());
+    for character in text.chars() {
+      if !character.is_whitespace() {
+        collapsed.push(character);
+        self.space_before = false;
+      } else if !self.space_before {
+        collapsed.push(' ');
+        self.space_before = true;
+      }
+    }
+    if collapsed.is_empty() {
+      return;
+    }
+
+    self.start_block();
+    self.tokens.push_text(span, &collapsed);
+  }
+
+  /// Drops a space from the end of the text that hasn't been written yet, handing back if
+  /// there was one.
+  fn trim_trailing_space(&mut self) -> bool {
+    let text = &mut self.tokens.text;
+    let trimmed = match *text {
+      Some((_, ref mut pending)) if pending.ends_with(' ') => pending.pop().is_some(),
+      _ => false,
+    };
+    if text.as_ref().is_some_and(|(_, pending)| pending.is_empty()) {
+      *text = None;
+    }
+    trimmed
+  }
+
+  /// Writes the block we're inside of, if it hasn't been already.
+  fn start_block(&mut self) {
+    if self.open_block.is_some() || self.inline > 0 {
+      return;
+    }
+    let (span, mapping) = match self.blocks.last() {
+      Some(block) => *block,
+      None => return,
+    };
+    self.tokens.flush_text();
+    match mapping {
+      ElementMapping::Paragraph => self.tokens.start(span, "p", &[]),
+      ElementMapping::Sentence => self.tokens.start(span, "s", &[]),
+      ElementMapping::ListItem => self.tokens.start_list_item(span),
+      ElementMapping::Heading => self.tokens.start_heading(span),
+      _ => {},
+    };
+    self.open_block = Some((span, mapping));
+  }
+
+  /// Closes the block that's been written, dropping any trailing whitespace.
+  fn end_block(&mut self) {
+    self.trim_trailing_space();
+    self.space_before = true;
+
+    self.tokens.flush_text();
+    let (span, mapping) = match self.open_block.take() {
+      Some(block) => block,
+      None => return,
+    };
+    match mapping {
+      ElementMapping::Paragraph => self.tokens.end(span, "p"),
+      ElementMapping::Sentence => self.tokens.end(span, "s"),
+      ElementMapping::ListItem => self.tokens.end_list_item(span),
+      ElementMapping::Heading => self.tokens.end_heading(span),
+      _ => {},
+    };
+  }
+
+  fn children(&mut self, element: ElementRef, span: Span) {
+    for child in element.children() {
+      if let Some(child) = ElementRef::wrap(child) {
+        self.element(child);
+      } else if let Node::Text(ref text) = *child.value() {
+        self.push_text(span, text);
+      }
+    }
+  }
+
+  fn block(&mut self, element: ElementRef, span: Span, mapping: ElementMapping) {
+    if self.inline > 0 {
+      self.push_text(span, " ");
+      self.children(element, span);
+      self.push_text(span, " ");
+      return;
+    }
+    self.end_block();
+    self.blocks.push((span, mapping));
+    self.children(element, span);
+    self.end_block();
+    self.blocks.pop();
+  }
+
+  fn inline(&mut self, element: ElementRef, span: Span, tag_key: &str, params: &[(&str, &str)]) {
+    self.start_block();
+    self.start(span, tag_key, params);
+    self.inline += 1;
+    self.children(element, span);
+    self.inline -= 1;
+
+    // Trailing whitespace is moved after the end tag, where it can be dropped with the
+    // rest of the block's.
+    let trailing_space = self.trim_trailing_space();
+    self.end(span, tag_key);
+    if trailing_space {
+      self.space_before = false;
+      self.push_text(span, " ");
+    }
+  }
+
+  fn element(&mut self, element: ElementRef) {
+    let name = element.value().name();
+    let span = self.locate(name);
+    match self.options.mapping(name) {
+      ElementMapping::Drop => {},
+      ElementMapping::Text => self.children(element, span),
+      mapping @ ElementMapping::Paragraph
+        | mapping @ ElementMapping::Sentence
+        | mapping @ ElementMapping::Heading
+        | mapping @ ElementMapping::ListItem => self.block(element, span, mapping),
+      ElementMapping::Emphasis(level) => self.inline(element, span, "emphasis", &[("level", &level.to_string())]),
+      ElementMapping::SubFromTitle => match element.value().attr("title") {
+        Some(title) => self.inline(element, span, "sub", &[("alias", title)]),
+        None => self.children(element, span),
+      },
+      ElementMapping::Break(strength) => {
+        self.start_block();
+        self.tokens.flush_text();
+        self.tokens.pause(span, strength);
+      },
+    };
+  }
+
+}
@@ -0,0 +1,419 @@
+//! Loads W3C Pronunciation Lexicon Specification (PLS) files, which map words (graphemes)
+//! to how they should be said, either as a phoneme, or as an alias. This is the same
+//! format Polly uses for it's own lexicons, the specification can be found:
+//! [HERE](https://www.w3.org/TR/pronunciation-lexicon/).
+//!
+//! Setting a lexicon on `ParseOptions` wraps every matching word in a `<phoneme>`, or
+//! `<sub>` tag while parsing, which is handy for brand names, and acronyms that Polly
+//! keeps getting wrong.
+//!
+//! Going the other way, `collect_lexicon` gathers every inline `phoneme`, and `sub` tag
+//! out of a pile of markup so they can be moved into a shared lexicon, and uploaded to
+//! Polly with `PutLexicon`.
+
+use failure::{Error, err_msg, format_err};
+use quick_xml::{Reader, Writer};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use crate::diagnostics::Diagnostic;
+use crate::document::{Document, Element, Node};
+use crate::parser::{OneItem, Span};
+use crate::ssml_constants::*;
+use crate::wrapping::{ends_word, rewrite_text, wrap_words};
+
+/// How a grapheme should be said.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Pronunciation {
+  /// Said using the phonetic pronunciation `ph`, written in `alphabet`.
+  Phoneme {
+    /// The alphabet `ph` is written in.
+    alphabet: PhonemeAlphabet,
+    /// The phonetic pronunciation.
+    ph: String,
+  },
+  /// Said as if it was this other text instead.
+  Alias(String),
+}
+
+/// A single entry in a lexicon. Every grapheme is said the same way.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Lexeme {
+  /// The words this entry matches.
+  pub graphemes: Vec<String>,
+  /// How each of the words should be said.
+  pub pronunciation: Pronunciation,
+}
+
+/// A pronunciation lexicon.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Lexicon {
+  /// The alphabet phonemes are written in, unless they say otherwise.
+  pub alphabet: PhonemeAlphabet,
+  /// The language the lexicon is for, e.g. `en-US`.
+  pub lang: Option<String>,
+  /// Every entry in the lexicon, in the order they were declared.
+  pub lexemes: Vec<Lexeme>,
+}
+
+/// The part of a lexeme currently being read.
+enum Reading {
+  Nothing,
+  Grapheme,
+  Phoneme(PhonemeAlphabet),
+  Alias,
+}
+
+impl Lexicon {
+
+  /// Loads a lexicon from a PLS document. When a lexeme has more than one pronunciation
+  /// the one marked `prefer="true"` is used, otherwise the first one is.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::lexicon::{Lexicon, Pronunciation};
+  /// let lexicon = Lexicon::from_pls(r#"<?xml version="1.0" encoding="UTF-8"?>
+  /// <lexicon version="1.0" xmlns="http://www.w3.org/2005/01/pronunciation-lexicon"
+  ///   alphabet="ipa" xml:lang="en-US">
+  ///   <lexeme><grapheme>W3C</grapheme><alias>World Wide Web Consortium</alias></lexeme>
+  /// </lexicon>"#).unwrap();
+  /// assert_eq!(lexicon.lookup("W3C"), Some(&Pronunciation::Alias("World Wide Web Consortium".to_owned())));
+  /// ```
+  pub fn from_pls(pls: &str) -> Result<Lexicon, Error> {
+    let mut reader = Reader::from_str(pls);
+    let mut buf = Vec::new();
+
+    let mut lexicon: Option<Lexicon> = None;
+    let mut graphemes: Vec<String> = Vec::new();
+    let mut pronunciations: Vec<(bool, Pronunciation)> = Vec::new();
+    let mut reading = Reading::Nothing;
+    let mut is_preferred = false;
+    let mut text = String::new();
+
+    loop {
+      match reader.read_event(&mut buf)? {
+        Event::Start(ref elem) => {
+          let mut attributes = BTreeMap::new();
+          for attribute in elem.attributes() {
+            let attribute = attribute?;
+            attributes.insert(
+              reader.decode(attribute.key).into_owned(),
+              attribute.unescape_and_decode_value(&reader)?,
+            );
+          }
+          text.clear();
+          is_preferred = attributes.get("prefer").map(String::as_str) == Some("true");
+
+          match elem.name() {
+            b"lexicon" => {
+              let alphabet = attributes.get("alphabet")
+                .ok_or_else(|| err_msg("The lexicon is missing it's alphabet!"))?;
+              lexicon = Some(Lexicon {
+                alphabet: parse_alphabet(alphabet)?,
+                lang: attributes.get("xml:lang").cloned(),
+                lexemes: Vec::new(),
+              });
+            },
+            b"lexeme" => {
+              graphemes.clear();
+              pronunciations.clear();
+            },
+            b"grapheme" => reading = Reading::Grapheme,
+            b"phoneme" => {
+              let alphabet = match (attributes.get("alphabet"), lexicon.as_ref()) {
+                (Some(alphabet), _) => parse_alphabet(alphabet)?,
+                (None, Some(lexicon)) => lexicon.alphabet,
+                (None, None) => return Err(err_msg("Found a phoneme outside of a lexicon!")),
+              };
+              reading = Reading::Phoneme(alphabet);
+            },
+            b"alias" => reading = Reading::Alias,
+            _ => {},
+          };
+        },
+        Event::Text(ref elem) => {
+          text.push_str(&elem.unescape_and_decode(&reader)?);
+        },
+        Event::CData(ref elem) => {
+          text.push_str(&reader.decode(elem.escaped()));
+        },
+        Event::End(ref elem) => {
+          let value = text.trim().to_owned();
+          match (elem.name(), &reading) {
+            (b"grapheme", Reading::Grapheme) => graphemes.push(value),
+            (b"phoneme", Reading::Phoneme(alphabet)) => {
+              pronunciations.push((is_preferred, Pronunciation::Phoneme { alphabet: *alphabet, ph: value }));
+            },
+            (b"alias", Reading::Alias) => pronunciations.push((is_preferred, Pronunciation::Alias(value))),
+            (b"lexeme", _) => {
+              let lexicon = lexicon.as_mut()
+                .ok_or_else(|| err_msg("Found a lexeme outside of a lexicon!"))?;
+              let preferred = pronunciations.iter().position(|(preferred, _)| *preferred).unwrap_or(0);
+              if graphemes.is_empty() || pronunciations.is_empty() {
+                return Err(format_err!("Every lexeme needs a grapheme, and a phoneme or alias: {:?}", graphemes));
+              }
+              lexicon.lexemes.push(Lexeme {
+                graphemes: graphemes.split_off(0),
+                pronunciation: pronunciations.swap_remove(preferred).1,
+              });
+            },
+            _ => {},
+          };
+          reading = Reading::Nothing;
+          text.clear();
+        },
+        Event::Eof => break,
+        _ => {},
+      };
+      buf.clear();
+    }
+
+    lexicon.ok_or_else(|| err_msg("No lexicon was found!"))
+  }
+
+  /// Loads a lexicon from a PLS file.
+  pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Lexicon, Error> {
+    Lexicon::from_pls(&fs::read_to_string(path)?)
+  }
+
+  /// Writes the lexicon out as a PLS document, which can be handed straight to Polly's
+  /// `PutLexicon`. Phonemes written in a different alphabet than the lexicon's are
+  /// marked with their own alphabet.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::lexicon::{Lexeme, Lexicon, Pronunciation};
+  /// use text_to_polly_ssml::ssml_constants::PhonemeAlphabet;
+  /// let lexicon = Lexicon {
+  ///   alphabet: PhonemeAlphabet::Ipa,
+  ///   lang: Some("en-US".to_owned()),
+  ///   lexemes: vec![Lexeme {
+  ///     graphemes: vec!["hg".to_owned()],
+  ///     pronunciation: Pronunciation::Alias("mercury".to_owned()),
+  ///   }],
+  /// };
+  /// let pls = lexicon.to_pls().unwrap();
+  /// assert!(pls.ends_with("<lexeme><grapheme>hg</grapheme><alias>mercury</alias></lexeme></lexicon>"));
+  /// assert_eq!(Lexicon::from_pls(&pls).unwrap(), lexicon);
+  /// ```
+  pub fn to_pls(&self) -> Result<String, Error> {
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    writer.write_event(Event::Decl(BytesDecl::new(b"1.0", Some(b"UTF-8"), None)))?;
+
+    let mut lexicon = BytesStart::owned(b"lexicon".to_vec(), "lexicon".len());
+    lexicon.push_attribute(("version", "1.0"));
+    lexicon.push_attribute(("xmlns", "http://www.w3.org/2005/01/pronunciation-lexicon"));
+    lexicon.push_attribute(("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"));
+    lexicon.push_attribute(("xsi:schemaLocation", "http://www.w3.org/2005/01/pronunciation-lexicon http://www.w3.org/TR/2007/CR-pronunciation-lexicon-20071212/pls.xsd"));
+    lexicon.push_attribute(("alphabet", &*self.alphabet.to_string()));
+    if let Some(ref lang) = self.lang {
+      lexicon.push_attribute(("xml:lang", lang.as_str()));
+    }
+    writer.write_event(Event::Start(lexicon))?;
+
+    for lexeme in &self.lexemes {
+      writer.write_event(Event::Start(BytesStart::borrowed(b"lexeme", "lexeme".len())))?;
+      for grapheme in &lexeme.graphemes {
+        write_pls_element(&mut writer, BytesStart::borrowed(b"grapheme", "grapheme".len()), grapheme)?;
+      }
+      match lexeme.pronunciation {
+        Pronunciation::Phoneme { alphabet, ref ph } => {
+          let mut phoneme = BytesStart::borrowed(b"phoneme", "phoneme".len());
+          if alphabet != self.alphabet {
+            phoneme.push_attribute(("alphabet", &*alphabet.to_string()));
+          }
+          write_pls_element(&mut writer, phoneme, ph)?;
+        },
+        Pronunciation::Alias(ref alias) => {
+          write_pls_element(&mut writer, BytesStart::borrowed(b"alias", "alias".len()), alias)?;
+        },
+      };
+      writer.write_event(Event::End(BytesEnd::borrowed(b"lexeme")))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::borrowed(b"lexicon")))?;
+    Ok(String::from_utf8(writer.into_inner().into_inner())?)
+  }
+
+  /// Finds how a grapheme should be said. Matching is case sensitive, and the first lexeme
+  /// that matches wins.
+  pub fn lookup(&self, grapheme: &str) -> Option<&Pronunciation> {
+    self.lexemes.iter()
+      .find(|lexeme| lexeme.graphemes.iter().any(|candidate| candidate == grapheme))
+      .map(|lexeme| &lexeme.pronunciation)
+  }
+
+}
+
+fn write_pls_element(writer: &mut Writer<Cursor<Vec<u8>>>, start: BytesStart, text: &str) -> Result<(), Error> {
+  let name = start.name().to_vec();
+  writer.write_event(Event::Start(start))?;
+  writer.write_event(Event::Text(BytesText::from_plain_str(text)))?;
+  writer.write_event(Event::End(BytesEnd::owned(name)))?;
+  Ok(())
+}
+
+fn parse_alphabet(alphabet: &str) -> Result<PhonemeAlphabet, Error> {
+  alphabet.parse::<PhonemeAlphabet>()
+    .map_err(|_| format_err!("Unsupported phoneme alphabet: {}", alphabet))
+}
+
+/// Wraps every word in the text tokens that's in the lexicon in a `phoneme`, or `sub` tag.
+/// Text already inside of a `phoneme`, or `sub` tag is left alone.
+pub(crate) fn apply_lexicon(items: Vec<(Span, OneItem)>, lexicon: &Lexicon) -> Vec<(Span, OneItem)> {
+  let mut graphemes = lexicon.lexemes.iter()
+    .flat_map(|lexeme| lexeme.graphemes.iter().map(move |grapheme| (grapheme.as_str(), &lexeme.pronunciation)))
+    .filter(|(grapheme, _)| !grapheme.is_empty())
+    .collect::<Vec<(&str, &Pronunciation)>>();
+  // Longest first, so `New York City` wins over `New York`. The sort is stable, so
+  // otherwise the first lexeme still wins.
+  graphemes.sort_by_key(|(grapheme, _)| std::cmp::Reverse(grapheme.len()));
+
+  let skip = |tag_key: &str| matches!(tag_key.parse::<PossibleOpenTags>(),
+    Ok(PossibleOpenTags::Phoneme) | Ok(PossibleOpenTags::Sub));
+  rewrite_text(items, skip, |text, span, result| {
+    wrap_words(text, span.start, |rest| {
+      graphemes.iter()
+        .find(|(grapheme, _)| rest.starts_with(grapheme) && ends_word(rest, grapheme.len()))
+        .map(|(grapheme, pronunciation)| {
+          let (tag_key, params) = pronunciation_tag(pronunciation);
+          (grapheme.len(), tag_key, params)
+        })
+    }, result);
+  })
+}
+
+fn pronunciation_tag(pronunciation: &Pronunciation) -> (String, BTreeMap<String, String>) {
+  let mut params = BTreeMap::new();
+  let tag_key = match *pronunciation {
+    Pronunciation::Phoneme { alphabet, ref ph } => {
+      params.insert("alphabet".to_owned(), alphabet.to_string());
+      params.insert("ph".to_owned(), ph.to_owned());
+      PossibleOpenTags::Phoneme.to_string()
+    },
+    Pronunciation::Alias(ref alias) => {
+      params.insert("alias".to_owned(), alias.to_owned());
+      PossibleOpenTags::Sub.to_string()
+    },
+  };
+  (tag_key, params)
+}
+
+/// A lexicon built up out of inline markup, along with every conflicting pronunciation
+/// found along the way.
+#[derive(Clone, Debug)]
+pub struct CollectedLexicon {
+  /// One lexeme per grapheme, using the first pronunciation seen for it.
+  pub lexicon: Lexicon,
+  /// A warning for every time a grapheme was said differently than the first time.
+  pub conflicts: Vec<Diagnostic>,
+}
+
+/// Collects every `phoneme`, and `sub` tag out of a corpus of markup into a single
+/// lexicon. Each document is a pair of a name (e.g. a file path, used when reporting
+/// conflicts), and it's markup. The text inside of each tag becomes the grapheme, and
+/// the first pronunciation seen for a grapheme is the one kept. The lexicon's alphabet
+/// is the alphabet of the first phoneme found, or IPA if there are none.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::lexicon::collect_lexicon;
+/// let collected = collect_lexicon(&[
+///   ("a.txt", "${sub|alias=mercury}hg${/sub} and ${phoneme|alphabet=ipa|ph=pɪˈkɑːn}pecan${/phoneme}"),
+///   ("b.txt", "${sub|alias=mercury}hg${/sub} and ${phoneme|alphabet=ipa|ph=ˈpiːkæn}pecan${/phoneme}"),
+/// ], Some("en-US")).unwrap();
+/// assert_eq!(collected.lexicon.lexemes.len(), 2);
+/// assert_eq!(collected.conflicts.len(), 1);
+/// ```
+pub fn collect_lexicon(documents: &[(&str, &str)], lang: Option<&str>) -> Result<CollectedLexicon, Error> {
+  let mut found: Vec<(String, Pronunciation, &str)> = Vec::new();
+  let mut conflicts = Vec::new();
+
+  for &(name, markup) in documents {
+    let mut pronunciations = Vec::new();
+    collect_pronunciations(&Document::from_markup(markup)?.nodes, &mut pronunciations);
+
+    for (grapheme, pronunciation, span) in pronunciations {
+      match found.iter().find(|(existing, _, _)| *existing == grapheme) {
+        Some((_, existing, source)) if *existing != pronunciation => {
+          conflicts.push(Diagnostic::warning(format!(
+            "\"{}\" in {} is said as {}, but as {} in {}. Keeping {}.",
+            grapheme, name, describe(&pronunciation), describe(existing), source, describe(existing),
+          ), span));
+        },
+        Some(_) => {},
+        None => found.push((grapheme, pronunciation, name)),
+      };
+    }
+  }
+
+  let alphabet = found.iter()
+    .filter_map(|(_, pronunciation, _)| match *pronunciation {
+      Pronunciation::Phoneme { alphabet, .. } => Some(alphabet),
+      Pronunciation::Alias(_) => None,
+    })
+    .next()
+    .unwrap_or(PhonemeAlphabet::Ipa);
+
+  Ok(CollectedLexicon {
+    lexicon: Lexicon {
+      alphabet,
+      lang: lang.map(str::to_owned),
+      lexemes: found.into_iter()
+        .map(|(grapheme, pronunciation, _)| Lexeme { graphemes: vec![grapheme], pronunciation })
+        .collect(),
+    },
+    conflicts,
+  })
+}
+
+fn collect_pronunciations(nodes: &[Node], found: &mut Vec<(String, Pronunciation, Option<Span>)>) {
+  for node in nodes {
+    let element = match *node {
+      Node::Element(ref element) => element,
+      Node::Text(_) => continue,
+    };
+    if let Some(pronunciation) = element_pronunciation(element) {
+      let grapheme = element_text(&element.children);
+      if !grapheme.trim().is_empty() {
+        found.push((grapheme.trim().to_owned(), pronunciation, element.span));
+      }
+    }
+    collect_pronunciations(&element.children, found);
+  }
+}
+
+fn element_pronunciation(element: &Element) -> Option<Pronunciation> {
+  match element.name.parse::<PossibleOpenTags>() {
+    Ok(PossibleOpenTags::Phoneme) => {
+      let alphabet = element.attributes.get("alphabet")?.parse::<PhonemeAlphabet>().ok()?;
+      let ph = element.attributes.get("ph")?.to_owned();
+      Some(Pronunciation::Phoneme { alphabet, ph })
+    },
+    Ok(PossibleOpenTags::Sub) => element.attributes.get("alias").cloned().map(Pronunciation::Alias),
+    _ => None,
+  }
+}
+
+fn element_text(nodes: &[Node]) -> String {
+  nodes.iter()
+    .map(|node| match *node {
+      Node::Text(ref text) => text.text.clone(),
+      Node::Element(ref element) => element_text(&element.children),
+    })
+    .collect()
+}
+
+fn describe(pronunciation: &Pronunciation) -> String {
+  match *pronunciation {
+    Pronunciation::Phoneme { alphabet, ref ph } => format!("the {} phoneme \"{}\"", alphabet, ph),
+    Pronunciation::Alias(ref alias) => format!("the alias \"{}\"", alias),
+  }
+}
@@ -1,5 +1,26 @@
+pub mod abbreviations;
+pub mod audio;
+pub mod builder;
+#[cfg(any(feature = "html", feature = "markdown"))]
+mod conversion;
+pub mod diagnostics;
+pub mod dialect;
+pub mod document;
+#[cfg(feature = "html")]
+pub mod html;
+pub mod lexicon;
+#[cfg(feature = "markdown")]
+pub mod markdown;
+pub mod normalization;
 pub mod parser;
+pub mod phonetics;
+pub mod plain_text;
+pub mod script;
+mod segmenter;
 pub mod ssml_constants;
+pub mod validator;
+pub mod voice;
+mod wrapping;
 pub mod xml_writer;
 
 use failure::Error;
@@ -9,3 +30,15 @@ use failure::Error;
 pub fn parse_string(to_parse: String) -> Result<String, Error> {
   parser::parse_as_ssml(to_parse)
 }
+
+/// Strips a String in the Unique Text to SSML Format down to just the text that will be
+/// spoken. Useful for transcripts, or anywhere else the tags would just get in the way.
+pub fn to_plain_text(to_parse: String) -> Result<String, Error> {
+  plain_text::markup_to_plain_text(&to_parse, &plain_text::PlainTextOptions::default())
+}
+
+/// Validates a String of SSML against what Polly supports, handing back every problem
+/// found. Useful for checking SSML that didn't come from `parse_string`.
+pub fn validate_ssml(ssml: &str) -> Vec<diagnostics::Diagnostic> {
+  validator::validate_ssml(ssml)
+}
@@ -0,0 +1,216 @@
+//! Converts Markdown into SSML, so documentation can be listened to as well as read. This
+//! needs the `markdown` feature.
+//!
+//!   * Headings are read louder, and slower in a paragraph of their own, after a strong
+//!     break.
+//!   * Emphasis, and strong emphasis become `<emphasis>` (or a louder `<prosody>`).
+//!   * Every list item is a sentence, followed by a break.
+//!   * Code spans are read as is, or spelled out with `say-as interpret-as="characters"`.
+//!   * Links are read as their text, while images, code blocks, and HTML are left out.
+//!
+//! `${...}` markup can be used anywhere in the Markdown, for anything Markdown can't say. Tags
+//! are set aside before the Markdown is parsed, so their parameters are taken as written.
+
+use failure::Error;
+use pulldown_cmark::{Event, Parser, Tag};
+
+use crate::conversion::{escape_markup, Tokens};
+use crate::dialect::Dialect;
+use crate::parser::{run_passes, tokenize_with_spans, write_tokens, OneItem, ParseOptions, ParseOutput, Span};
+use crate::ssml_constants::*;
+
+/// Options for controlling how Markdown gets converted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MarkdownOptions {
+  /// If code spans are spelled out letter by letter. Otherwise they're read like any other
+  /// text.
+  pub spell_out_code: bool,
+  /// If emphasis is written as a louder `prosody`, rather than `emphasis`.
+  pub emphasis_as_prosody: bool,
+}
+
+/// Converts `markdown` into SSML for `dialect`. The markup inside of the Markdown is parsed
+/// with `options`, and diagnostics point into the Markdown.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::dialect::PollyDialect;
+/// use text_to_polly_ssml::markdown::{markdown_to_ssml, MarkdownOptions};
+/// use text_to_polly_ssml::parser::ParseOptions;
+/// let output = markdown_to_ssml(
+///   "# Setup\n\nRun it *twice*. ${break|time=1s}",
+///   &MarkdownOptions::default(),
+///   &ParseOptions::default(),
+///   PollyDialect::default(),
+/// ).unwrap();
+/// assert!(output.ssml.ends_with(concat!(
+///   r#"><break strength="strong"/><p><prosody volume="loud" rate="slow">Setup</prosody></p>"#,
+///   r#"<p>Run it <emphasis level="moderate">twice</emphasis>. <break time="1s"/></p></speak>"#,
+/// )));
+/// ```
+pub fn markdown_to_ssml<D: Dialect>(markdown: &str, markdown_options: &MarkdownOptions, options: &ParseOptions,
+  dialect: D) -> Result<ParseOutput, Error> {
+  let mut diagnostics = Vec::new();
+  let parsed = run_passes(markdown_tokens(markdown, markdown_options)?, options, &mut diagnostics)?;
+  let ssml = write_tokens(&parsed, options, dialect, &mut diagnostics)?;
+  Ok(ParseOutput {
+    ssml,
+    diagnostics,
+  })
+}
+
+/// Turns Markdown into the same tokens the parser hands back for markup.
+fn markdown_tokens(markdown: &str, options: &MarkdownOptions) -> Result<Vec<(Span, OneItem)>, Error> {
+  let masked = mask_tags(markdown)?;
+  let mut converter = Converter {
+    options,
+    source: markdown,
+    masked: &masked,
+    tokens: Tokens::new(),
+    left_out: 0,
+    sentences: Vec::new(),
+  };
+  for (event, range) in Parser::new(&masked).into_offset_iter() {
+    converter.event(event, Span { start: range.start, end: range.end })?;
+  }
+  converter.tokens.flush_markup()?;
+  Ok(converter.tokens.items)
+}
+
+/// Blanks out every `${...}` tag in the Markdown (keeping it the same length), so the
+/// Markdown parser can't find emphasis, code, etc. inside of a tag's parameters. `$` is
+/// used since it's what the tag starts with, so the text around it is parsed the same.
+fn mask_tags(markdown: &str) -> Result<String, Error> {
+  let mut masked = markdown.as_bytes().to_vec();
+  for (span, item) in tokenize_with_spans(markdown)? {
+    if item.start_tag.is_some() || item.end_tag.is_some() {
+      for byte in masked[span.start..span.end].iter_mut().filter(|byte| **byte != b'\n') {
+        *byte = b'$';
+      }
+    }
+  }
+  Ok(String::from_utf8(masked)?)
+}
+
+/// Keeps track of where we are in the Markdown while converting it.
+struct Converter<'a> {
+  options: &'a MarkdownOptions,
+  /// The Markdown as it was written.
+  source: &'a str,
+  /// The Markdown that's actually parsed, see `mask_tags`.
+  masked: &'a str,
+  /// Markdown splits text up in odd places, so it's collected until the next tag before
+  /// being tokenized, to keep markup in one piece.
+  tokens: Tokens,
+  /// How many images, or code blocks we're inside of.
+  left_out: usize,
+  /// Every list item we're inside of, and if it's sentence is still open.
+  sentences: Vec<bool>,
+}
+
+impl<'a> Converter<'a> {
+
+  /// The text of an event as it was written, putting back any tags `mask_tags` blanked
+  /// out of it.
+  fn unmask(&self, text: &str, span: Span) -> String {
+    let masked = &self.masked[span.start..span.end];
+    let source = &self.source[span.start..span.end];
+    if masked == source {
+      return text.to_owned();
+    }
+    // Text with escapes in it doesn't line up with the Markdown, so it's taken as written.
+    masked.find(text)
+      .and_then(|offset| source.get(offset..offset + text.len()))
+      .unwrap_or(source)
+      .to_owned()
+  }
+
+  fn start_emphasis(&mut self, span: Span, level: EmphasisLevel) {
+    match level.as_prosody() {
+      Some((volume, rate)) if self.options.emphasis_as_prosody => {
+        let rate = rate.map(|rate| rate.to_string());
+        let mut params = vec![("volume", volume)];
+        if let Some(ref rate) = rate {
+          params.push(("rate", rate));
+        }
+        self.tokens.start(span, "prosody", &params);
+      },
+      _ => self.tokens.start(span, "emphasis", &[("level", &level.to_string())]),
+    };
+  }
+
+  fn end_emphasis(&mut self, span: Span) {
+    let tag_key = if self.options.emphasis_as_prosody { "prosody" } else { "emphasis" };
+    self.tokens.end(span, tag_key);
+  }
+
+  fn event(&mut self, event: Event, span: Span) -> Result<(), Error> {
+    if self.left_out > 0 {
+      match event {
+        Event::Start(Tag::Image(..)) | Event::Start(Tag::CodeBlock(_)) => self.left_out += 1,
+        Event::End(Tag::Image(..)) | Event::End(Tag::CodeBlock(_)) => self.left_out -= 1,
+        _ => {},
+      };
+      return Ok(());
+    }
+
+    match event {
+      Event::Text(ref text) => {
+        let text = self.unmask(text, span);
+        self.tokens.push_text(span, &text);
+        return Ok(());
+      },
+      Event::SoftBreak => {
+        self.tokens.push_text(span, " ");
+        return Ok(());
+      },
+      _ => self.tokens.flush_markup()?,
+    };
+
+    match event {
+      Event::Start(Tag::Paragraph) if self.sentences.is_empty() => self.tokens.start(span, "p", &[]),
+      Event::End(Tag::Paragraph) if self.sentences.is_empty() => self.tokens.end(span, "p"),
+      Event::End(Tag::Paragraph) => self.tokens.push_text(span, " "),
+      Event::Start(Tag::Heading(..)) => self.tokens.start_heading(span),
+      Event::End(Tag::Heading(..)) => self.tokens.end_heading(span),
+      Event::Start(Tag::List(_)) => {
+        // A sentence can't hold another sentence, so a nested list ends the sentence for
+        // the item it's in (along with it's break).
+        if let Some(sentence) = self.sentences.last_mut().filter(|sentence| **sentence) {
+          *sentence = false;
+          self.tokens.end_list_item(span);
+        }
+      },
+      Event::Start(Tag::Item) => {
+        self.sentences.push(true);
+        self.tokens.start_list_item(span);
+      },
+      Event::End(Tag::Item) => {
+        let sentence_open = self.sentences.pop() == Some(true);
+        if sentence_open {
+          self.tokens.end_list_item(span);
+        }
+      },
+      Event::Start(Tag::Emphasis) => self.start_emphasis(span, EmphasisLevel::Moderate),
+      Event::Start(Tag::Strong) => self.start_emphasis(span, EmphasisLevel::Strong),
+      Event::End(Tag::Emphasis) | Event::End(Tag::Strong) => self.end_emphasis(span),
+      Event::Start(Tag::Image(..)) | Event::Start(Tag::CodeBlock(_)) => self.left_out += 1,
+      Event::Code(code) => {
+        let code = OneItem::text(&escape_markup(&self.unmask(&code, span)));
+        if self.options.spell_out_code {
+          self.tokens.start(span, "say-as", &[("interpret-as", "characters")]);
+          self.tokens.items.push((span, code));
+          self.tokens.end(span, "say-as");
+        } else {
+          self.tokens.items.push((span, code));
+        }
+      },
+      Event::HardBreak => self.tokens.pause(span, BreakStrength::Weak),
+      Event::Rule => self.tokens.pause(span, BreakStrength::XStrong),
+      _ => {},
+    };
+    Ok(())
+  }
+
+}
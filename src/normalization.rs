@@ -0,0 +1,353 @@
+//! Text normalization. Polly's guesses at how to read things like `3/4`, `$1.5M`, `10km`,
+//! or `2024-03-05` aren't always consistent, so this finds them in text, and wraps them in
+//! a `say-as`, or `sub` tag that says exactly how they should be read.
+//!
+//! Text already inside of a `say-as`, `sub`, or `phoneme` tag is left alone. Which
+//! patterns are looked for, and the locale conventions used (e.g. if `03/05/2024` is March
+//! 5th, or May 3rd) are controlled with `NormalizationOptions`. Amounts of money are only
+//! written out in English, so they're left for the engine in other locales.
+
+use regex::Regex;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::document::{Document, Element, Node, Text};
+use crate::parser::{OneItem, Span};
+use crate::ssml_constants::*;
+use crate::wrapping::rewrite_text;
+
+/// Every kind of text normalization knows how to find. When two patterns match the same
+/// text, the one declared first wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pattern {
+  /// Dates, such as `2024-03-05`, or `03/05/2024`. Read as a `date`.
+  Date,
+  /// Telephone numbers, such as `(555) 123-4567`. Read as a `telephone`.
+  Telephone,
+  /// Amounts of money with a scale, such as `$1.5M`. Substituted with English words, so
+  /// it's only looked for in English locales.
+  Currency,
+  /// Measurements, such as `10km`. Read as a `unit`.
+  Unit,
+  /// Fractions, such as `3/4`. Read as a `fraction`.
+  Fraction,
+  /// Numbers that are really identifiers, such as `#1234`, or `007`. Read as `digits`.
+  Digits,
+}
+
+impl Pattern {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = Pattern> {
+    const VARIANTS: &[Pattern] = &[
+      Pattern::Date,
+      Pattern::Telephone,
+      Pattern::Currency,
+      Pattern::Unit,
+      Pattern::Fraction,
+      Pattern::Digits,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
+impl fmt::Display for Pattern {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Pattern::Date => write!(f, "date"),
+      Pattern::Telephone => write!(f, "telephone"),
+      Pattern::Currency => write!(f, "currency"),
+      Pattern::Unit => write!(f, "unit"),
+      Pattern::Fraction => write!(f, "fraction"),
+      Pattern::Digits => write!(f, "digits"),
+    }
+  }
+}
+
+impl FromStr for Pattern {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Pattern, ()> {
+      match &*s.to_lowercase() {
+        "date" => Ok(Pattern::Date),
+        "telephone" => Ok(Pattern::Telephone),
+        "currency" => Ok(Pattern::Currency),
+        "unit" => Ok(Pattern::Unit),
+        "fraction" => Ok(Pattern::Fraction),
+        "digits" => Ok(Pattern::Digits),
+        _ => Err(()),
+      }
+    }
+}
+
+/// Options for controlling text normalization.
+#[derive(Clone, Debug)]
+pub struct NormalizationOptions {
+  /// The locale whose conventions are used. This only decides the order of dates (`en-US`
+  /// reads `03/05/2024` month first, where every other locale reads it day first), and if
+  /// amounts of money are substituted, which only happens for English (`en-*`) locales.
+  pub locale: String,
+  /// The patterns to look for.
+  pub patterns: Vec<Pattern>,
+}
+
+impl Default for NormalizationOptions {
+  fn default() -> NormalizationOptions {
+    NormalizationOptions {
+      locale: "en-US".to_owned(),
+      patterns: Pattern::variants().collect(),
+    }
+  }
+}
+
+/// A run of text after normalization, either left as is, or wrapped in a tag.
+enum Piece {
+  Text(String),
+  Wrapped(String, BTreeMap<String, String>, String),
+}
+
+/// A single pattern found in some text.
+struct Found {
+  start: usize,
+  end: usize,
+  tag: String,
+  params: BTreeMap<String, String>,
+  text: String,
+}
+
+/// The compiled regular expressions for every pattern that's turned on.
+struct Normalizer<'a> {
+  options: &'a NormalizationOptions,
+  patterns: Vec<(Pattern, Regex)>,
+}
+
+impl<'a> Normalizer<'a> {
+
+  fn new(options: &'a NormalizationOptions) -> Normalizer<'a> {
+    let patterns = Pattern::variants()
+      .filter(|pattern| options.patterns.contains(pattern))
+      .filter(|pattern| *pattern != Pattern::Currency || is_english(&options.locale))
+      .map(|pattern| {
+        let expression = match pattern {
+          Pattern::Date => r"(\d{4})-(\d{2})-(\d{2})|(\d{1,2})/(\d{1,2})/(\d{4}|\d{2})",
+          Pattern::Telephone => r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{3}\) ?|\d{3}[ .-])\d{3}[ .-]\d{4}",
+          Pattern::Currency => r"([$€£¥])(\d+(?:\.\d+)?) ?(thousand|million|billion|bn|[KkMB])",
+          Pattern::Unit => r"\d+(?:\.\d+)?(?:km/h|km|cm|mm|mi|mph|kph|kg|mg|lbs|lb|oz|ml|GB|MB|KB|TB|°C|°F|m|g|l|ft)",
+          Pattern::Fraction => r"\d+/\d+",
+          Pattern::Digits => r"#(\d+)|0\d+",
+        };
+        (pattern, Regex::new(expression).unwrap())
+      })
+      .collect();
+
+    Normalizer {
+      options,
+      patterns,
+    }
+  }
+
+  /// Splits some text up into the parts that should be wrapped, and the parts that shouldn't.
+  fn normalize(&self, text: &str) -> Vec<(Span, Piece)> {
+    let mut found: Vec<Found> = Vec::new();
+    for (pattern, regex) in &self.patterns {
+      for captures in regex.captures_iter(text) {
+        let whole = captures.get(0).unwrap();
+        let is_bounded = !is_joined(text[..whole.start()].chars().rev()) && !is_joined(text[whole.end()..].chars());
+        let overlaps = found.iter().any(|other| whole.start() < other.end && other.start < whole.end());
+        if !is_bounded || overlaps {
+          continue;
+        }
+        if let Some(piece) = self.wrap(*pattern, &captures) {
+          found.push(piece);
+        }
+      }
+    }
+    found.sort_by_key(|piece| piece.start);
+
+    let mut pieces = Vec::new();
+    let mut last_end = 0;
+    for piece in found {
+      if last_end < piece.start {
+        pieces.push((Span { start: last_end, end: piece.start }, Piece::Text(text[last_end..piece.start].to_owned())));
+      }
+      pieces.push((Span { start: piece.start, end: piece.end }, Piece::Wrapped(piece.tag, piece.params, piece.text)));
+      last_end = piece.end;
+    }
+    if last_end < text.len() {
+      pieces.push((Span { start: last_end, end: text.len() }, Piece::Text(text[last_end..].to_owned())));
+    }
+    pieces
+  }
+
+  fn wrap(&self, pattern: Pattern, captures: &regex::Captures) -> Option<Found> {
+    let whole = captures.get(0).unwrap();
+    let mut params = BTreeMap::new();
+    let mut text = whole.as_str().to_owned();
+    let mut start = whole.start();
+
+    let tag = match pattern {
+      Pattern::Date => {
+        let format = if captures.get(1).is_some() {
+          let month = captures[2].parse::<u32>().ok()?;
+          let day = captures[3].parse::<u32>().ok()?;
+          if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+          }
+          "ymd"
+        } else {
+          let first = captures[4].parse::<u32>().ok()?;
+          let second = captures[5].parse::<u32>().ok()?;
+          let (month, day, format) = if self.is_month_first() { (first, second, "mdy") } else { (second, first, "dmy") };
+          if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+          }
+          format
+        };
+        params.insert("format".to_owned(), format.to_owned());
+        PossibleOpenTags::SayAs
+      },
+      Pattern::Telephone => PossibleOpenTags::SayAs,
+      Pattern::Currency => {
+        let currency = match &captures[1] {
+          "$" => "dollars",
+          "€" => "euros",
+          "£" => "pounds",
+          _ => "yen",
+        };
+        let scale = match &captures[3] {
+          "K" | "k" | "thousand" => "thousand",
+          "M" | "million" => "million",
+          _ => "billion",
+        };
+        params.insert("alias".to_owned(), format!("{} {} {}", &captures[2], scale, currency));
+        PossibleOpenTags::Sub
+      },
+      Pattern::Unit => PossibleOpenTags::SayAs,
+      Pattern::Fraction => PossibleOpenTags::SayAs,
+      Pattern::Digits => {
+        if let Some(digits) = captures.get(1) {
+          start = digits.start();
+          text = digits.as_str().to_owned();
+        }
+        PossibleOpenTags::SayAs
+      },
+    };
+
+    if tag == PossibleOpenTags::SayAs {
+      let interpret_as = if pattern == Pattern::Digits { "digits".to_owned() } else { pattern.to_string() };
+      params.insert("interpret-as".to_owned(), interpret_as);
+    }
+
+    Some(Found {
+      start,
+      end: whole.end(),
+      tag: tag.to_string(),
+      params,
+      text,
+    })
+  }
+
+  fn is_month_first(&self) -> bool {
+    self.options.locale.eq_ignore_ascii_case("en-US")
+  }
+
+}
+
+/// If the characters next to a match (walking away from it) mean it's really part of
+/// something bigger, e.g. the `03` in `2024-03-05`, the `3/4` in `3/4/5`, the `050` in
+/// `1,050`, or the `05` in `10:05`.
+fn is_joined<I: Iterator<Item = char>>(mut beside: I) -> bool {
+  match beside.next() {
+    Some(character) if character.is_alphanumeric() || character == '/' => true,
+    Some('-') | Some('.') => beside.next().is_some_and(char::is_alphanumeric),
+    Some(',') | Some(':') => beside.next().is_some_and(|character| character.is_ascii_digit()),
+    _ => false,
+  }
+}
+
+/// If `locale` is for any kind of English, e.g. `en-GB`.
+fn is_english(locale: &str) -> bool {
+  locale.split(['-', '_']).next()
+    .is_some_and(|language| language.eq_ignore_ascii_case("en"))
+}
+
+/// If text inside of this tag has already been told how to be read.
+fn is_already_normalized(name: &str) -> bool {
+  matches!(name.parse::<PossibleOpenTags>(),
+    Ok(PossibleOpenTags::SayAs) | Ok(PossibleOpenTags::Sub) | Ok(PossibleOpenTags::Phoneme))
+}
+
+/// Normalizes every text node in a document, wrapping dates, telephone numbers, amounts of
+/// money, measurements, fractions, and identifiers in the tag that reads them correctly.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::document::Document;
+/// use text_to_polly_ssml::normalization::{normalize, NormalizationOptions};
+/// let mut document = Document::from_markup("Raised $1.5M on 2024-03-05.").unwrap();
+/// normalize(&mut document, &NormalizationOptions::default());
+/// assert!(document.render().unwrap().ends_with(concat!(
+///   r#">Raised <sub alias="1.5 million dollars">$1.5M</sub> on "#,
+///   r#"<say-as interpret-as="date" format="ymd">2024-03-05</say-as>.</speak>"#,
+/// )));
+/// ```
+pub fn normalize(document: &mut Document, options: &NormalizationOptions) {
+  let normalizer = Normalizer::new(options);
+  normalize_nodes(&mut document.nodes, &normalizer);
+}
+
+fn normalize_nodes(nodes: &mut Vec<Node>, normalizer: &Normalizer) {
+  let mut normalized = Vec::with_capacity(nodes.len());
+
+  for node in nodes.drain(..) {
+    match node {
+      Node::Element(mut element) => {
+        if !is_already_normalized(&element.name) {
+          normalize_nodes(&mut element.children, normalizer);
+        }
+        normalized.push(Node::Element(element));
+      },
+      Node::Text(text) => {
+        // Only point into the markup when the text wasn't unescaped, otherwise the
+        // offsets wouldn't line up.
+        let offset = text.span.filter(|span| span.end - span.start == text.text.len()).map(|span| span.start);
+        for (span, piece) in normalizer.normalize(&text.text) {
+          let span = offset.map(|offset| Span { start: offset + span.start, end: offset + span.end }).or(text.span);
+          normalized.push(match piece {
+            Piece::Text(text) => Node::Text(Text { text, span }),
+            Piece::Wrapped(tag, params, text) => {
+              let mut element = Element::new(&tag, params);
+              element.children.push(Node::Text(Text { text, span }));
+              element.span = span;
+              Node::Element(element)
+            },
+          });
+        }
+      },
+    };
+  }
+
+  *nodes = normalized;
+}
+
+/// The same as `normalize`, but for the parser's tokens.
+pub(crate) fn normalize_tokens(items: Vec<(Span, OneItem)>, options: &NormalizationOptions) -> Vec<(Span, OneItem)> {
+  let normalizer = Normalizer::new(options);
+  rewrite_text(items, is_already_normalized, |text, span, result| {
+    for (piece_span, piece) in normalizer.normalize(text) {
+      let piece_span = Span { start: span.start + piece_span.start, end: span.start + piece_span.end };
+      match piece {
+        Piece::Text(text) => result.push((piece_span, OneItem::text(&text))),
+        Piece::Wrapped(tag, params, text) => {
+          result.push((piece_span, OneItem::start(&tag, params)));
+          result.push((piece_span, OneItem::text(&text)));
+          result.push((piece_span, OneItem::end(&tag)));
+        },
+      };
+    }
+  })
+}
@@ -1,9 +1,17 @@
-use failure::{Error, err_msg};
+use failure::{Error, err_msg, format_err};
 use nom::*;
 
+use crate::abbreviations::{apply_abbreviations, AbbreviationDictionary};
+use crate::audio::{check_audio, close_audio_tags, is_audio_end, is_audio_start, is_fallback, AudioCheck, AudioOptions};
+use crate::dialect::{Dialect, PollyDialect};
+use crate::diagnostics::Diagnostic;
+use crate::lexicon::{apply_lexicon, Lexicon};
+use crate::normalization::{normalize_tokens, NormalizationOptions};
+use crate::segmenter::segment;
 use crate::ssml_constants::*;
 use crate::xml_writer::XmlWriter;
 
+use std::io::{Cursor, Write};
 use std::str;
 use std::collections::BTreeMap;
 
@@ -18,6 +26,66 @@ pub struct EndTag {
   pub tag_key: String,
 }
 
+/// Where in the original text something came from, as byte offsets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+  /// The offset of the first byte.
+  pub start: usize,
+  /// The offset just past the last byte.
+  pub end: usize,
+}
+
+/// Options for controlling how text gets parsed.
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+  /// If anything the engine wouldn't accept as is should fail parsing. When this is off
+  /// it gets fixed up instead, with a warning explaining what changed.
+  pub strict: bool,
+  /// The longest break the engine supports.
+  pub max_break_time: BreakTime,
+  /// Words to wrap in a `phoneme`, or `sub` tag automatically. Text that's already inside
+  /// of a `phoneme`, or `sub` tag is left alone.
+  pub lexicon: Option<Lexicon>,
+  /// If text outside of any tag should be wrapped in `p`, and `s` tags automatically.
+  /// Blocks separated by a blank line become paragraphs, and each sentence inside of them
+  /// becomes a sentence. Text already inside of a `p`, or `s` tag is left alone.
+  pub segment: bool,
+  /// If set, dates, telephone numbers, amounts of money, measurements, fractions, and
+  /// identifiers in the text are wrapped in the tag that reads them correctly.
+  pub normalization: Option<NormalizationOptions>,
+  /// Abbreviations to expand, and acronyms to spell out. Text that's already inside of a
+  /// `sub`, `say-as`, or `phoneme` tag is left alone.
+  pub abbreviations: Option<AbbreviationDictionary>,
+  /// If set, audio tags pointing at local files (`file` URLs, or relative paths) are
+  /// checked on disk, which is handy for catching broken sounds during offline builds.
+  pub audio: Option<AudioOptions>,
+}
+
+impl Default for ParseOptions {
+  fn default() -> ParseOptions {
+    ParseOptions {
+      strict: false,
+      max_break_time: BreakTime::POLLY_MAX,
+      lexicon: None,
+      segment: false,
+      normalization: None,
+      abbreviations: None,
+      audio: None,
+    }
+  }
+}
+
+/// The result of parsing with options, the SSML along with everything we had to fix up
+/// along the way.
+#[derive(Clone, Debug)]
+pub struct ParseOutput {
+  /// The generated SSML.
+  pub ssml: String,
+  /// Every warning produced while parsing.
+  pub diagnostics: Vec<Diagnostic>,
+}
+
 #[derive(Clone, Debug)]
 pub struct OneItem {
   pub start_tag: Option<StartTag>,
@@ -25,8 +93,44 @@ pub struct OneItem {
   pub data: Option<String>,
 }
 
+impl OneItem {
+
+  /// A token for a run of text.
+  pub(crate) fn text(data: &str) -> OneItem {
+    OneItem {
+      start_tag: None,
+      end_tag: None,
+      data: Some(data.to_owned()),
+    }
+  }
+
+  /// A token for a start tag.
+  pub(crate) fn start(tag_key: &str, params: BTreeMap<String, String>) -> OneItem {
+    OneItem {
+      start_tag: Some(StartTag {
+        tag_key: tag_key.to_owned(),
+        params,
+      }),
+      end_tag: None,
+      data: None,
+    }
+  }
+
+  /// A token for an end tag.
+  pub(crate) fn end(tag_key: &str) -> OneItem {
+    OneItem {
+      start_tag: None,
+      end_tag: Some(EndTag {
+        tag_key: tag_key.to_owned(),
+      }),
+      data: None,
+    }
+  }
+
+}
+
 named!(
-  string<&str>,
+  string<&[u8], &str>,
   alt!(
     map_res!(take_until!("${"), str::from_utf8) |
     map_res!(rest, str::from_utf8)
@@ -44,27 +148,23 @@ named!(
       (key)
     ),
     |key: &str| {
-      if key.contains("|") {
-        let mut as_split = key.split("|");
+      if key.contains('|') {
+        let mut as_split = key.split('|');
         let tag_key = as_split.next().unwrap().to_owned();
         let mut parsed_out_values = BTreeMap::new();
-        loop {
-          match as_split.next() {
-            Some(x) => {
-              let mut as_split_new = x.split("=");
-              let btree_key = as_split_new.next();
-              let btree_value = as_split_new.next();
-              if btree_key.is_none() || btree_value.is_none() {
-                break
-              }
-              parsed_out_values.insert(btree_key.unwrap().to_owned(),
-                btree_value.unwrap().to_owned());
+        for x in as_split {
+          let mut as_split_new = x.split('=');
+          let btree_key = as_split_new.next();
+          let btree_value = as_split_new.next();
+          match (btree_key, btree_value) {
+            (Some(btree_key), Some(btree_value)) => {
+              parsed_out_values.insert(btree_key.to_owned(), btree_value.to_owned());
             },
-            None => { break }
+            _ => { break }
           };
         }
         StartTag {
-          tag_key: tag_key,
+          tag_key,
           params: parsed_out_values,
         }
       } else {
@@ -95,8 +195,8 @@ named!(
 );
 
 named!(
-  text_to_ssml_parser<Vec<OneItem>>,
-  many1!(complete!(alt!(
+  one_item<OneItem>,
+  complete!(alt!(
     map!(
       start_tag_info,
       |start_tag| {
@@ -125,216 +225,627 @@ named!(
         }
       }
     )
-  )))
+  ))
 );
 
+/// Splits some text into the stream of tokens the rest of the crate works off of. Each
+/// token is either a start tag, an end tag, or a run of text. No validation of tag names or
+/// parameters happens here, so unknown tags are handed back as is. Escaped tag openings
+/// (`$\{`) inside text are left escaped.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::tokenize;
+/// let tokens = tokenize("${p}hey${/p}").unwrap();
+/// assert_eq!(tokens.len(), 3);
+/// assert_eq!(tokens[1].data, Some("hey".to_owned()));
+/// ```
+pub fn tokenize(data: &str) -> Result<Vec<OneItem>, Error> {
+  Ok(tokenize_with_spans(data)?.into_iter().map(|(_, item)| item).collect())
+}
+
+/// The same as `tokenize`, but also hands back where in the original text each token
+/// came from.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{tokenize_with_spans, Span};
+/// let tokens = tokenize_with_spans("${p}hey${/p}").unwrap();
+/// assert_eq!(tokens[1].0, Span { start: 4, end: 7 });
+/// ```
+pub fn tokenize_with_spans(data: &str) -> Result<Vec<(Span, OneItem)>, Error> {
+  let mut items = Vec::new();
+  let mut input = data.as_bytes();
+
+  while let IResult::Done(rest, item) = one_item(input) {
+    // Much like nom's `many1!`, stop as soon as we stop making progress.
+    if !items.is_empty() && rest.len() == input.len() {
+      break;
+    }
+    let start = data.len() - input.len();
+    items.push((Span { start, end: data.len() - rest.len() }, item));
+    input = rest;
+    if input.is_empty() {
+      break;
+    }
+  }
+
+  if items.is_empty() {
+    return Err(err_msg("Failed to parse text!"));
+  }
+  Ok(items)
+}
+
 /// Parses some text as SSML. It should note the error here allows for a lot of wiggle room.
 /// It's still totally possible to generate invalid SSML with this. This simply does what the
 /// user tells it too. If a user doesn't close a tag, we won't close a tag. If they close a
 /// tag without opening one we won't close it. If they include a paragraph tag inside a paragraph
 /// tag we'll still render it. All of these are invalid SSML, but don't trigger an error.
 /// This is meant to be that way as you can try anything with SSML, since polly doesn't fully
-/// follow the SSML v1.1 spec, now you can play around as much as you want.
+/// follow the SSML v1.1 spec, now you can play around as much as you want. The one
+/// exception is breaks longer than Polly allows, which get cut down to Polly's limit.
 pub fn parse_as_ssml(data: String) -> Result<String, Error> {
-  let res = text_to_ssml_parser(data.as_bytes());
-  if res.is_err() {
-    println!("{:?}", res);
-    return Err(err_msg("Failed to parse text!"));
+  Ok(parse_with_options(&data, &ParseOptions::default())?.ssml)
+}
+
+/// The same as `parse_as_ssml`, but with control over how things the engine wouldn't
+/// accept get handled. In strict mode they fail parsing, otherwise they're fixed up, and
+/// a warning is handed back for each one.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{parse_with_options, ParseOptions};
+/// let output = parse_with_options("${break|time=12.5s}", &ParseOptions::default()).unwrap();
+/// assert!(output.ssml.contains(r#"<break time="10s"/>"#));
+/// assert_eq!(output.diagnostics.len(), 1);
+///
+/// let options = ParseOptions { strict: true, ..ParseOptions::default() };
+/// assert!(parse_with_options("${break|time=12.5s}", &options).is_err());
+/// ```
+pub fn parse_with_options(data: &str, options: &ParseOptions) -> Result<ParseOutput, Error> {
+  parse_with_dialect(data, options, PollyDialect::default())
+}
+
+/// The same as `parse_with_options`, but writes the SSML for another engine. Anything the
+/// dialect had to change, or leave out is handed back as a warning, or in strict mode
+/// fails parsing.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::dialect::GoogleDialect;
+/// use text_to_polly_ssml::parser::{parse_with_dialect, ParseOptions};
+/// let output = parse_with_dialect("${amazon:effect|name=whispered}psst${/amazon:effect}",
+///   &ParseOptions::default(), GoogleDialect::default()).unwrap();
+/// assert!(output.ssml.ends_with(r#"<speak><prosody volume="x-soft" rate="slow">psst</prosody></speak>"#));
+/// assert_eq!(output.diagnostics.len(), 1);
+/// ```
+pub fn parse_with_dialect<D: Dialect>(data: &str, options: &ParseOptions, dialect: D) -> Result<ParseOutput, Error> {
+  let mut diagnostics = Vec::new();
+  let parsed = prepare_tokens(data, options, &mut diagnostics)?;
+  let ssml = write_tokens(&parsed, options, dialect, &mut diagnostics)?;
+  Ok(ParseOutput {
+    ssml,
+    diagnostics,
+  })
+}
+
+/// Tokenizes `data`, and runs every pass `options` turns on over the tokens.
+pub(crate) fn prepare_tokens(data: &str, options: &ParseOptions,
+  diagnostics: &mut Vec<Diagnostic>) -> Result<Vec<(Span, OneItem)>, Error> {
+  run_passes(tokenize_with_spans(data)?, options, diagnostics)
+}
+
+/// Runs every pass `options` turns on over tokens.
+pub(crate) fn run_passes(mut parsed: Vec<(Span, OneItem)>, options: &ParseOptions,
+  diagnostics: &mut Vec<Diagnostic>) -> Result<Vec<(Span, OneItem)>, Error> {
+  check_break_times(&mut parsed, options, diagnostics)?;
+  parsed = check_audio(close_audio_tags(parsed), options, diagnostics)?;
+  if options.segment {
+    parsed = segment(parsed);
+  }
+  if let Some(ref normalization) = options.normalization {
+    parsed = normalize_tokens(parsed, normalization);
+  }
+  if let Some(ref abbreviations) = options.abbreviations {
+    parsed = apply_abbreviations(parsed, abbreviations);
+  }
+  if let Some(ref lexicon) = options.lexicon {
+    parsed = apply_lexicon(parsed, lexicon);
+  }
+  Ok(parsed)
+}
+
+/// Writes a whole SSML document for `parsed` with `dialect`.
+pub(crate) fn write_tokens<D: Dialect>(parsed: &[(Span, OneItem)], options: &ParseOptions, mut dialect: D,
+  diagnostics: &mut Vec<Diagnostic>) -> Result<String, Error> {
+  let mut xml_writer = if dialect.xml_declaration() {
+    XmlWriter::new()?
+  } else {
+    XmlWriter::from_writer_without_declaration(Cursor::new(Vec::new()))
+  };
+  let mut dialect_diagnostics = Vec::new();
+  dialect.start_speak(&mut xml_writer)?;
+
+  for (span, item) in parsed {
+    write_item(&mut dialect, &mut xml_writer, *span, item, &mut dialect_diagnostics)?;
+  }
+
+  dialect.end_speak(&mut xml_writer)?;
+
+  if options.strict {
+    if let Some(diagnostic) = dialect_diagnostics.first() {
+      return Err(format_err!("{}", diagnostic));
+    }
+  }
+  diagnostics.extend(dialect_diagnostics);
+  xml_writer.into_string()
+}
+
+/// Makes sure no break is longer than the engine allows. In strict mode a break that's
+/// too long is an error, otherwise it's cut down to the longest break allowed.
+fn check_break_times(items: &mut [(Span, OneItem)], options: &ParseOptions,
+  diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+  for (span, item) in items.iter_mut() {
+    check_break_time(*span, item, options, diagnostics)?;
+  }
+  Ok(())
+}
+
+/// The same as `check_break_times`, for a single token.
+fn check_break_time(span: Span, item: &mut OneItem, options: &ParseOptions,
+  diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+  let start_tag = match item.start_tag {
+    Some(ref mut start_tag) => start_tag,
+    None => return Ok(()),
+  };
+  let time = match break_time(start_tag) {
+    Some(time) if time > options.max_break_time => time,
+    _ => return Ok(()),
+  };
+
+  let message = format!("A break of {} is longer than the limit of {}.", time, options.max_break_time);
+  if options.strict {
+    return Err(format_err!("{} ({}..{})", message, span.start, span.end));
   }
-  let (_, parsed) = res.unwrap();
+  start_tag.params.insert("time".to_owned(), options.max_break_time.to_string());
+  diagnostics.push(Diagnostic::warning(message, Some(span)));
+  Ok(())
+}
+
+/// How long a `break` tag is, if it is one, and has a valid `time`.
+pub(crate) fn break_time(start_tag: &StartTag) -> Option<BreakTime> {
+  if start_tag.tag_key.parse::<PossibleOpenTags>() != Ok(PossibleOpenTags::Break) {
+    return None;
+  }
+  start_tag.params.get("time")?.parse::<BreakTime>().ok()
+}
+
+/// Hands a single token to a dialect to write out.
+fn write_item<D: Dialect, W: Write>(dialect: &mut D, xml_writer: &mut XmlWriter<W>, span: Span, item: &OneItem,
+  diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+  if let Some(ref start_tag) = item.start_tag {
+    dialect.start_tag(xml_writer, start_tag, span, diagnostics)?;
+  }
+  if let Some(ref end_tag) = item.end_tag {
+    dialect.end_tag(xml_writer, end_tag, span, diagnostics)?;
+  }
+  if let Some(ref data) = item.data {
+    dialect.text(xml_writer, data.replace("$\\{", "${").as_str(), span, diagnostics)?;
+  }
+  Ok(())
+}
+
+/// Writes the SSML for a single start tag. Unknown tags, or tags missing required
+/// parameters are skipped.
+pub(crate) fn write_start_tag<W: Write>(xml_writer: &mut XmlWriter<W>, start_tag: &StartTag) {
+  let as_tag = start_tag.tag_key.clone().parse::<PossibleOpenTags>();
+  if as_tag.is_err() {
+    return;
+  }
+  let tag_frd = as_tag.unwrap();
+
+  match tag_frd {
+    PossibleOpenTags::Break => {
+      let mut strength: Option<BreakStrength> = None;
+      let mut time: Option<BreakTime> = None;
+
+      if start_tag.params.contains_key("strength") {
+        if let Ok(attempted_parse) = start_tag.params.get("strength").unwrap()
+          .parse::<BreakStrength>() {
+          strength = Some(attempted_parse);
+        }
+      }
+      if start_tag.params.contains_key("time") {
+        if let Ok(attempted_parse) = start_tag.params.get("time").unwrap()
+          .parse::<BreakTime>() {
+          time = Some(attempted_parse);
+        }
+      }
+      let _ = xml_writer.ssml_break(strength, time);
+    },
+    PossibleOpenTags::LangTag => {
+      if !start_tag.params.contains_key("lang") {
+        return;
+      }
+      let lang = start_tag.params.get("lang").unwrap().to_owned();
+      let mut onlangfailure: Option<String> = None;
+      if start_tag.params.contains_key("onlangfailure") {
+        onlangfailure = Some(start_tag.params.get("onlangfailure").unwrap().to_owned());
+      }
+      let _ = xml_writer.start_ssml_lang(lang, onlangfailure);
+    },
+    PossibleOpenTags::Mark => {
+      if !start_tag.params.contains_key("name") {
+        return;
+      }
+      let name = start_tag.params.get("name").unwrap().to_owned();
+      let _ = xml_writer.start_ssml_mark(name);
+    }
+    PossibleOpenTags::Paragraph => {
+      let _ = xml_writer.start_ssml_paragraph();
+    },
+    PossibleOpenTags::Phoneme => {
+      if !start_tag.params.contains_key("alphabet") ||
+        !start_tag.params.contains_key("ph") {
+        return;
+      }
+      let potential_alphabet = start_tag.params.get("alphabet").unwrap()
+        .parse::<PhonemeAlphabet>();
+      if potential_alphabet.is_err() {
+        return;
+      }
+      let alphabet = potential_alphabet.unwrap();
+      let ph = start_tag.params.get("ph").unwrap().to_owned();
+      let _ = xml_writer.start_ssml_phoneme(alphabet, ph);
+    },
+    PossibleOpenTags::Prosody => {
+      let mut volume: Option<String> = None;
+      let mut rate: Option<ProsodyRate> = None;
+      let mut pitch: Option<String> = None;
 
-  let mut xml_writer = XmlWriter::new()?;
-  xml_writer.start_ssml_speak(None, None)?;
+      if start_tag.params.contains_key("volume") {
+        volume = Some(start_tag.params.get("volume").unwrap().to_owned());
+      }
+      if start_tag.params.contains_key("rate") {
+        if let Ok(potentially_parsed) = start_tag.params.get("rate").unwrap()
+          .parse::<ProsodyRate>() {
+          rate = Some(potentially_parsed);
+        }
+      }
+      if start_tag.params.contains_key("pitch") {
+        pitch = Some(start_tag.params.get("pitch").unwrap().to_owned());
+      }
 
-  let _ = parsed.into_iter().inspect(|item| {
-    if let Some(ref start_tag) = item.start_tag {
-      let as_tag = start_tag.tag_key.clone().parse::<PossibleOpenTags>();
-      if as_tag.is_err() {
+      let _ = xml_writer.start_ssml_prosody(volume, rate, pitch);
+    },
+    PossibleOpenTags::Sentence => {
+      let _ = xml_writer.start_ssml_sentence();
+    },
+    PossibleOpenTags::SayAs => {
+      if !start_tag.params.contains_key("interpret-as") {
         return;
       }
-      let tag_frd = as_tag.unwrap();
-
-      match tag_frd {
-        PossibleOpenTags::Break => {
-          let mut strength: Option<BreakStrength> = None;
-          let mut time: Option<BreakTime> = None;
-
-          if start_tag.params.contains_key("strength") {
-            let attempted_parse = start_tag.params.get("strength").unwrap()
-              .parse::<BreakStrength>();
-            if attempted_parse.is_ok() {
-              strength = Some(attempted_parse.unwrap());
-            }
-          }
-          if start_tag.params.contains_key("time") {
-            let attempted_parse = start_tag.params.get("time").unwrap()
-              .parse::<BreakTime>();
-            if attempted_parse.is_ok() {
-              time = Some(attempted_parse.unwrap());
-            }
-          }
-          let _ = xml_writer.ssml_break(strength, time);
-        },
-        PossibleOpenTags::LangTag => {
-          if !start_tag.params.contains_key("lang") {
-            return;
-          }
-          let lang = start_tag.params.get("lang").unwrap().to_owned();
-          let mut onlangfailure: Option<String> = None;
-          if start_tag.params.contains_key("onlangfailure") {
-            onlangfailure = Some(start_tag.params.get("onlangfailure").unwrap().to_owned());
-          }
-          let _ = xml_writer.start_ssml_lang(lang, onlangfailure);
-        },
-        PossibleOpenTags::Mark => {
-          if !start_tag.params.contains_key("name") {
-            return;
-          }
-          let name = start_tag.params.get("name").unwrap().to_owned();
-          let _ = xml_writer.start_ssml_mark(name);
+      let interpret_as = start_tag.params.get("interpret-as").unwrap().to_owned();
+      if let Some(format) = start_tag.params.get("format") {
+        let _ = xml_writer.start_ssml_say_as_with_format(interpret_as, format.to_owned());
+      } else {
+        let _ = xml_writer.start_ssml_say_as(interpret_as);
+      }
+    },
+    PossibleOpenTags::Sub => {
+      if !start_tag.params.contains_key("alias") {
+        return;
+      }
+      let alias = start_tag.params.get("alias").unwrap().to_owned();
+      let _ = xml_writer.start_ssml_sub(alias);
+    },
+    PossibleOpenTags::Word => {
+      if !start_tag.params.contains_key("role") {
+        return;
+      }
+      if let Ok(potentially_parsed) = start_tag.params.get("role").unwrap()
+        .parse::<WordRole>() {
+        let _ = xml_writer.start_ssml_w(potentially_parsed);
+      }
+    },
+    PossibleOpenTags::Emphasis => {
+      let level = start_tag.params.get("level").and_then(|level| level.parse::<EmphasisLevel>().ok());
+      let _ = xml_writer.start_ssml_emphasis(level);
+    },
+    // Polly can't play audio, only the fallback text inside of it gets said.
+    PossibleOpenTags::Audio => {},
+    // Polly picks the voice per request, see `voice::split_by_voice`.
+    PossibleOpenTags::Voice => {},
+    PossibleOpenTags::AmazonEffect => {
+      if !start_tag.params.contains_key("name") &&
+        !start_tag.params.contains_key("vocal-tract-length") &&
+        !start_tag.params.contains_key("phonation") {
+        return;
+      }
+      if start_tag.params.contains_key("name") {
+        if let Ok(potentially_parsed) = start_tag.params.get("name").unwrap()
+          .parse::<AmazonEffect>() {
+          let _ = xml_writer.start_ssml_amazon_effect(potentially_parsed);
         }
-        PossibleOpenTags::Paragraph => {
-          let _ = xml_writer.start_ssml_paragraph();
-        },
-        PossibleOpenTags::Phoneme => {
-          if !start_tag.params.contains_key("alphabet") ||
-            !start_tag.params.contains_key("ph") {
-            return;
-          }
-          let potential_alphabet = start_tag.params.get("alphabet").unwrap()
-            .parse::<PhonemeAlphabet>();
-          if potential_alphabet.is_err() {
-            return;
-          }
-          let alphabet = potential_alphabet.unwrap();
-          let ph = start_tag.params.get("ph").unwrap().to_owned();
-          let _ = xml_writer.start_ssml_phoneme(alphabet, ph);
-        },
-        PossibleOpenTags::Prosody => {
-          let mut volume: Option<String> = None;
-          let mut rate: Option<ProsodyRate> = None;
-          let mut pitch: Option<String> = None;
-
-          if start_tag.params.contains_key("volume") {
-            volume = Some(start_tag.params.get("volume").unwrap().to_owned());
-          }
-          if start_tag.params.contains_key("rate") {
-            let potentially_parsed = start_tag.params.get("rate").unwrap()
-              .parse::<ProsodyRate>();
-            if potentially_parsed.is_ok() {
-              rate = Some(potentially_parsed.unwrap());
-            }
-          }
-          if start_tag.params.contains_key("pitch") {
-            pitch = Some(start_tag.params.get("pitch").unwrap().to_owned());
-          }
-
-          let _ = xml_writer.start_ssml_prosody(volume, rate, pitch);
-        },
-        PossibleOpenTags::Sentence => {
-          let _ = xml_writer.start_ssml_sentence();
-        },
-        PossibleOpenTags::SayAs => {
-          if !start_tag.params.contains_key("interpret-as") {
-            return;
-          }
-          let interpret_as = start_tag.params.get("interpret-as").unwrap().to_owned();
-          let _ = xml_writer.start_ssml_say_as(interpret_as);
-        },
-        PossibleOpenTags::Sub => {
-          if !start_tag.params.contains_key("alias") {
-            return;
-          }
-          let alias = start_tag.params.get("alias").unwrap().to_owned();
-          let _ = xml_writer.start_ssml_sub(alias);
-        },
-        PossibleOpenTags::Word => {
-          if !start_tag.params.contains_key("role") {
-            return;
-          }
-          let potentially_parsed = start_tag.params.get("role").unwrap()
-            .parse::<WordRole>();
-          if potentially_parsed.is_ok() {
-            let _ = xml_writer.start_ssml_w(potentially_parsed.unwrap());
-          }
-        },
-        PossibleOpenTags::AmazonEffect => {
-          if !start_tag.params.contains_key("name") &&
-            !start_tag.params.contains_key("vocal-tract-length") &&
-            !start_tag.params.contains_key("phonation") {
-            return;
-          }
-          if start_tag.params.contains_key("name") {
-            let potentially_parsed = start_tag.params.get("name").unwrap()
-              .parse::<AmazonEffect>();
-            if potentially_parsed.is_ok() {
-              let _ = xml_writer.start_ssml_amazon_effect(potentially_parsed.unwrap());
-            }
-          } else if start_tag.params.contains_key("vocal-tract-length") {
-            let factor = start_tag.params.get("vocal-tract-length").unwrap();
-            let _ = xml_writer.start_ssml_vocal_tract_length(factor.to_owned());
-          } else {
-            let potentially_parsed = start_tag.params.get("phonation").unwrap()
-              .parse::<PhonationVolume>();
-            if potentially_parsed.is_ok() {
-              let _ = xml_writer.start_ssml_phonation(potentially_parsed.unwrap());
-            }
-          }
-        },
-        PossibleOpenTags::AmazonAutoBreaths => {
-          let volume = start_tag.params.get("volume").unwrap_or(&"".to_owned())
-            .parse::<BreathVolumes>();
-          let frequency = start_tag.params.get("frequency").unwrap_or(&"".to_owned())
-            .parse::<AutoBreathFrequency>();
-          let duration = start_tag.params.get("duration").unwrap_or(&"".to_owned())
-            .parse::<BreathDuration>();
-
-          if volume.is_ok() && frequency.is_ok() && duration.is_ok() {
-            let _ = xml_writer.start_ssml_auto_breaths(volume.unwrap(), frequency.unwrap(),
-              duration.unwrap());
-          }
-        },
-        PossibleOpenTags::AmazonBreath => {
-          let volume = start_tag.params.get("volume").unwrap_or(&"".to_owned())
-            .parse::<BreathVolumes>();
-          let duration = start_tag.params.get("duration").unwrap_or(&"".to_owned())
-            .parse::<BreathDuration>();
-
-          if volume.is_ok() && duration.is_ok() {
-            let _ = xml_writer.write_amazon_breath(volume.unwrap(), duration.unwrap());
-          }
-        },
-      };
-    };
+      } else if start_tag.params.contains_key("vocal-tract-length") {
+        let factor = start_tag.params.get("vocal-tract-length").unwrap();
+        let _ = xml_writer.start_ssml_vocal_tract_length(factor.to_owned());
+      } else {
+        if let Ok(potentially_parsed) = start_tag.params.get("phonation").unwrap()
+          .parse::<PhonationVolume>() {
+          let _ = xml_writer.start_ssml_phonation(potentially_parsed);
+        }
+      }
+    },
+    PossibleOpenTags::AmazonAutoBreaths => {
+      let volume = start_tag.params.get("volume").unwrap_or(&"".to_owned())
+        .parse::<BreathVolumes>();
+      let frequency = start_tag.params.get("frequency").unwrap_or(&"".to_owned())
+        .parse::<AutoBreathFrequency>();
+      let duration = start_tag.params.get("duration").unwrap_or(&"".to_owned())
+        .parse::<BreathDuration>();
 
-    if let Some(ref end_tag) = item.end_tag {
-      let as_tag = end_tag.tag_key.clone().parse::<PossibleClosingTags>();
-      if as_tag.is_err() {
-        return
+      if let (Ok(volume), Ok(frequency), Ok(duration)) = (volume, frequency, duration) {
+        let _ = xml_writer.start_ssml_auto_breaths(volume, frequency, duration);
       }
-      let tag_frd = as_tag.unwrap();
-
-      let _ = match tag_frd {
-        PossibleClosingTags::LangTag => xml_writer.end_ssml_lang(),
-        PossibleClosingTags::Mark => xml_writer.end_ssml_mark(),
-        PossibleClosingTags::Paragraph => xml_writer.end_ssml_paragraph(),
-        PossibleClosingTags::Phoneme => xml_writer.end_ssml_phoneme(),
-        PossibleClosingTags::Prosody => xml_writer.end_ssml_prosody(),
-        PossibleClosingTags::Sentence => xml_writer.end_ssml_sentence(),
-        PossibleClosingTags::SayAs => xml_writer.end_ssml_say_as(),
-        PossibleClosingTags::Sub => xml_writer.end_ssml_sub(),
-        PossibleClosingTags::Word => xml_writer.end_ssml_w(),
-        PossibleClosingTags::AmazonEffect => xml_writer.end_ssml_amazon_effect(),
-        PossibleClosingTags::AmazonAutoBreaths => xml_writer.end_ssml_amazon_auto_breaths(),
-      };
+    },
+    PossibleOpenTags::AmazonBreath => {
+      let volume = start_tag.params.get("volume").unwrap_or(&"".to_owned())
+        .parse::<BreathVolumes>();
+      let duration = start_tag.params.get("duration").unwrap_or(&"".to_owned())
+        .parse::<BreathDuration>();
+
+      if let (Ok(volume), Ok(duration)) = (volume, duration) {
+        let _ = xml_writer.write_amazon_breath(volume, duration);
+      }
+    },
+  };
+}
+
+/// Writes the SSML for a single end tag. Unknown tags are skipped.
+pub(crate) fn write_end_tag<W: Write>(xml_writer: &mut XmlWriter<W>, end_tag: &EndTag) {
+  let as_tag = end_tag.tag_key.clone().parse::<PossibleClosingTags>();
+  if as_tag.is_err() {
+    return
+  }
+  let tag_frd = as_tag.unwrap();
+
+  let _ = match tag_frd {
+    PossibleClosingTags::LangTag => xml_writer.end_ssml_lang(),
+    PossibleClosingTags::Mark => xml_writer.end_ssml_mark(),
+    PossibleClosingTags::Paragraph => xml_writer.end_ssml_paragraph(),
+    PossibleClosingTags::Phoneme => xml_writer.end_ssml_phoneme(),
+    PossibleClosingTags::Prosody => xml_writer.end_ssml_prosody(),
+    PossibleClosingTags::Sentence => xml_writer.end_ssml_sentence(),
+    PossibleClosingTags::SayAs => xml_writer.end_ssml_say_as(),
+    PossibleClosingTags::Sub => xml_writer.end_ssml_sub(),
+    PossibleClosingTags::Word => xml_writer.end_ssml_w(),
+    PossibleClosingTags::Emphasis => xml_writer.end_ssml_emphasis(),
+    PossibleClosingTags::Audio | PossibleClosingTags::Voice => Ok(0),
+    PossibleClosingTags::AmazonEffect => xml_writer.end_ssml_amazon_effect(),
+    PossibleClosingTags::AmazonAutoBreaths => xml_writer.end_ssml_amazon_auto_breaths(),
+  };
+}
+
+/// The most a `StreamingParser` holds onto waiting for a tag's closing `}`. Past this the
+/// tag is written out as text instead.
+pub const MAX_TAG_LENGTH: usize = 4096;
+
+/// An incremental version of `parse_as_ssml`. Rather than needing the entire text up front,
+/// text can be fed in as it arrives (e.g. for live captioning), and SSML is written out as
+/// soon as each tag, or run of text is complete. A tag that gets split across two chunks
+/// (e.g. `${prosody|vol` + `ume=+6dB}`) is held onto until the rest of it shows up, for up
+/// to `MAX_TAG_LENGTH` bytes.
+///
+/// There's no telling if the text after an `${audio|src=...}` tag is it's fallback without
+/// waiting for the rest of the stream, so in a stream it always is, up until the next tag.
+/// Audio that stands on it's own should be closed right away (`${audio|src=a.mp3}${/audio}`).
+/// Breaks, and audio sources are checked the same as `parse_with_options` does, under the
+/// options given to `with_options` (the default options otherwise). The rest of the passes
+/// (normalization, abbreviations, etc.) need the whole text, so they aren't run.
+///
+/// Just like `parse_as_ssml` the output is wrapped in a `<speak>` tag, which gets closed
+/// when calling `finish`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::StreamingParser;
+/// let mut parser = StreamingParser::new(Vec::new()).unwrap();
+/// assert_eq!(parser.feed("hey ${prosody|vol").unwrap().len(), 1);
+/// assert_eq!(parser.feed("ume=+6dB}loud${/prosody}").unwrap().len(), 3);
+/// let ssml = String::from_utf8(parser.finish().unwrap()).unwrap();
+/// assert!(ssml.ends_with(r#">hey <prosody volume="+6dB">loud</prosody></speak>"#));
+/// ```
+pub struct StreamingParser<W: Write = Cursor<Vec<u8>>, D: Dialect = PollyDialect> {
+  xml_writer: XmlWriter<W>,
+  dialect: D,
+  buffer: String,
+  offset: usize,
+  diagnostics: Vec<Diagnostic>,
+  options: ParseOptions,
+  audio_check: AudioCheck,
+  in_audio: bool,
+  audio_fallback: bool,
+}
+
+impl<W: Write> StreamingParser<W> {
+
+  /// Creates a new Streaming Parser writing Polly's SSML into `inner`. The XML header, and
+  /// the opening `<speak>` tag are written immediately.
+  pub fn new(inner: W) -> Result<StreamingParser<W>, Error> {
+    StreamingParser::with_dialect(inner, PollyDialect::default())
+  }
+
+}
+
+impl<W: Write, D: Dialect> StreamingParser<W, D> {
+
+  /// Creates a new Streaming Parser writing another engine's SSML into `inner`.
+  pub fn with_dialect(inner: W, mut dialect: D) -> Result<StreamingParser<W, D>, Error> {
+    let mut xml_writer = if dialect.xml_declaration() {
+      XmlWriter::from_writer(inner)?
+    } else {
+      XmlWriter::from_writer_without_declaration(inner)
     };
+    dialect.start_speak(&mut xml_writer)?;
+    Ok(StreamingParser {
+      xml_writer,
+      dialect,
+      buffer: String::new(),
+      offset: 0,
+      diagnostics: Vec::new(),
+      options: ParseOptions::default(),
+      audio_check: AudioCheck::default(),
+      in_audio: false,
+      audio_fallback: false,
+    })
+  }
 
-    if let Some(ref data) = item.data {
-      let _ = xml_writer.write_text(data.replace("$\\{", "${").as_str());
+  /// Checks breaks, and audio sources against `options`. In strict mode anything that
+  /// would have been fixed up fails `feed` instead.
+  pub fn with_options(mut self, options: ParseOptions) -> Self {
+    self.options = options;
+    self
+  }
+
+  /// Allows audio tags to point at local files, under the given options.
+  pub fn with_audio(mut self, options: AudioOptions) -> Self {
+    self.options.audio = Some(options);
+    self
+  }
+
+  /// Everything the dialect had to change, or leave out so far. Spans are byte offsets
+  /// into all of the text fed in.
+  pub fn diagnostics(&self) -> &[Diagnostic] {
+    &self.diagnostics
+  }
+
+  /// Feeds the next chunk of text into the parser. Every token that is now complete is
+  /// written out, and handed back so the caller can react to them. Anything that might
+  /// still be the start of a tag is kept around until the next call.
+  pub fn feed(&mut self, chunk: &str) -> Result<Vec<OneItem>, Error> {
+    self.buffer.push_str(chunk);
+    let mut items = Vec::new();
+
+    let mut start = self.offset;
+    while let Some(mut item) = self.next_complete_item()? {
+      let span = Span { start, end: self.offset };
+      start = self.offset;
+      check_break_time(span, &mut item, &self.options, &mut self.diagnostics)?;
+      if self.audio_check.keep(span, &item, self.options.audio.as_ref(), self.options.strict, &mut self.diagnostics)? {
+        self.write_streamed(span, item, &mut items)?;
+      }
     }
-  }).count();
 
-  xml_writer.end_ssml_speak()?;
+    Ok(items)
+  }
+
+  /// Writes out a token, closing an audio tag first if the token can't be part of it's
+  /// fallback.
+  fn write_streamed(&mut self, span: Span, item: OneItem, items: &mut Vec<OneItem>) -> Result<(), Error> {
+    if self.in_audio && !is_fallback(&item) {
+      self.in_audio = false;
+      if !is_audio_end(&item) {
+        self.end_audio(span, items)?;
+      }
+    }
+    if is_audio_start(&item) {
+      self.in_audio = true;
+      self.audio_fallback = false;
+    } else if self.in_audio && item.data.as_ref().is_some_and(|data| !data.trim().is_empty()) {
+      self.audio_fallback = true;
+    }
+    write_item(&mut self.dialect, &mut self.xml_writer, span, &item, &mut self.diagnostics)?;
+    items.push(item);
+    Ok(())
+  }
+
+  /// Closes an audio tag that was never closed with an `${/audio}`.
+  fn end_audio(&mut self, span: Span, items: &mut Vec<OneItem>) -> Result<(), Error> {
+    if self.audio_fallback {
+      self.diagnostics.push(Diagnostic::warning(
+        "<audio> wasn't closed with ${/audio}, so the text after it was used as it's fallback.".to_owned(), Some(span)));
+    }
+    let end = OneItem::end("audio");
+    write_item(&mut self.dialect, &mut self.xml_writer, span, &end, &mut self.diagnostics)?;
+    items.push(end);
+    Ok(())
+  }
+
+  /// Finishes parsing. Anything left over (such as a tag that was never closed with a `}`)
+  /// is written out as plain text, the `<speak>` tag is closed, and the underlying writer
+  /// is handed back.
+  pub fn finish(mut self) -> Result<W, Error> {
+    if self.in_audio {
+      self.end_audio(Span { start: self.offset, end: self.offset }, &mut Vec::new())?;
+    }
+    if !self.buffer.is_empty() {
+      let span = Span { start: self.offset, end: self.offset + self.buffer.len() };
+      let leftover = OneItem::text(&self.buffer.split_off(0));
+      write_item(&mut self.dialect, &mut self.xml_writer, span, &leftover, &mut self.diagnostics)?;
+    }
+    self.dialect.end_speak(&mut self.xml_writer)?;
+    Ok(self.xml_writer.into_inner())
+  }
+
+  /// Takes a tag that's gone on for longer than any tag could, up until the next tag, as
+  /// text. Otherwise a stray `${` would hold onto everything fed in after it.
+  fn unterminated_tag(&mut self) -> OneItem {
+    let text_end = self.buffer[2..].find("${").map(|text_end| text_end + 2).unwrap_or(self.buffer.len());
+    let span = Span { start: self.offset, end: self.offset + text_end };
+    self.offset += text_end;
+    self.diagnostics.push(Diagnostic::warning(
+      format!("A tag wasn't closed within {} bytes, so it was written as text.", MAX_TAG_LENGTH), Some(span)));
+    OneItem::text(&self.buffer.drain(..text_end).collect::<String>())
+  }
+
+  fn next_complete_item(&mut self) -> Result<Option<OneItem>, Error> {
+    if self.buffer.starts_with("${") {
+      let tag_end = match self.buffer.find('}') {
+        Some(tag_end) => tag_end + 1,
+        None if self.buffer.len() > MAX_TAG_LENGTH => return Ok(Some(self.unterminated_tag())),
+        None => return Ok(None),
+      };
+      let tag: String = self.buffer.drain(..tag_end).collect();
+      self.offset += tag.len();
+      let item = if tag.starts_with("${/") {
+        end_tag_info(tag.as_bytes()).to_result().map(|end_tag| OneItem {
+          start_tag: None,
+          end_tag: Some(end_tag),
+          data: None,
+        })
+      } else {
+        start_tag_info(tag.as_bytes()).to_result().map(|start_tag| OneItem {
+          start_tag: Some(start_tag),
+          end_tag: None,
+          data: None,
+        })
+      };
+      return item.map(Some).map_err(|_| err_msg("Failed to parse text!"));
+    }
+
+    // A trailing `$`, or `$\` could be the start of a tag, or an escaped tag, so hold
+    // onto it until we know for sure.
+    let text_end = match self.buffer.find("${") {
+      Some(text_end) => text_end,
+      None if self.buffer.ends_with('$') => self.buffer.len() - 1,
+      None if self.buffer.ends_with("$\\") => self.buffer.len() - 2,
+      None => self.buffer.len(),
+    };
+    if text_end == 0 {
+      return Ok(None);
+    }
+    self.offset += text_end;
+    Ok(Some(OneItem {
+      start_tag: None,
+      end_tag: None,
+      data: Some(self.buffer.drain(..text_end).collect()),
+    }))
+  }
 
-  Ok(xml_writer.render())
 }
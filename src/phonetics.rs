@@ -0,0 +1,178 @@
+//! Checks, and converts the phonetic pronunciations (`ph`) given to `phoneme` tags. Polly
+//! supports both IPA, and X-SAMPA, but will refuse a `ph` with symbols that aren't part of
+//! the alphabet, or that the voice's language doesn't use. The symbols each language
+//! supports can be found: [HERE](https://docs.aws.amazon.com/polly/latest/dg/ph-table.html).
+//!
+//! Converting lets you author in whichever alphabet you're comfortable with, and emit
+//! whichever one the target voice expects.
+
+use failure::{Error, format_err};
+
+use crate::diagnostics::Diagnostic;
+use crate::parser::Span;
+use crate::ssml_constants::PhonemeAlphabet;
+
+/// Every symbol we know about as an (IPA, X-SAMPA) pair. When a symbol appears more than
+/// once, the first entry is the one used when converting into that alphabet.
+const SYMBOLS: &[(&str, &str)] = &[
+  // Consonants.
+  ("b", "b"), ("ɓ", "b_<"), ("β", "B"), ("ʙ", "B\\"), ("c", "c"), ("ç", "C"), ("d", "d"),
+  ("ɗ", "d_<"), ("ɖ", "d`"), ("ð", "D"), ("f", "f"), ("ɡ", "g"), ("g", "g"), ("ɠ", "g_<"),
+  ("ɣ", "G"), ("ɢ", "G\\"), ("ʛ", "G\\_<"), ("h", "h"), ("ɦ", "h\\"), ("ħ", "X\\"),
+  ("ɥ", "H"), ("ʜ", "H\\"), ("j", "j"), ("ʝ", "j\\"), ("ɟ", "J\\"), ("ʄ", "J\\_<"),
+  ("ɲ", "J"), ("k", "k"), ("ɬ", "K"), ("ɮ", "K\\"), ("l", "l"), ("ɫ", "5"), ("ɭ", "l`"),
+  ("ɺ", "l\\"), ("ʎ", "L"), ("ʟ", "L\\"), ("m", "m"), ("ɱ", "F"), ("ɰ", "M\\"), ("n", "n"),
+  ("ɳ", "n`"), ("ŋ", "N"), ("ɴ", "N\\"), ("p", "p"), ("ɸ", "p\\"), ("q", "q"), ("r", "r"),
+  ("ɹ", "r\\"), ("ɻ", "r\\`"), ("ɽ", "r`"), ("ɾ", "4"), ("ʀ", "R\\"), ("ʁ", "R"), ("s", "s"),
+  ("ʂ", "s`"), ("ɕ", "s\\"), ("ʃ", "S"), ("t", "t"), ("ʈ", "t`"), ("θ", "T"), ("v", "v"),
+  ("ʋ", "v\\"), ("w", "w"), ("ʍ", "W"), ("x", "x"), ("ɧ", "x\\"), ("χ", "X"), ("z", "z"),
+  ("ʐ", "z`"), ("ʑ", "z\\"), ("ʒ", "Z"), ("ʔ", "?"), ("ʕ", "?\\"), ("ʢ", "<\\"),
+  ("ʡ", ">\\"), ("ʘ", "O\\"), ("ǀ", "|\\"), ("ǁ", "|\\|\\"), ("ǂ", "=\\"), ("ǃ", "!\\"),
+  // Vowels.
+  ("a", "a"), ("ɐ", "6"), ("ɑ", "A"), ("ɒ", "Q"), ("æ", "{"), ("e", "e"), ("ə", "@"),
+  ("ɚ", "@`"), ("ɘ", "@\\"), ("ɛ", "E"), ("ɜ", "3"), ("ɝ", "3`"), ("ɞ", "3\\"), ("i", "i"),
+  ("ɪ", "I"), ("ɨ", "1"), ("o", "o"), ("ɔ", "O"), ("ø", "2"), ("œ", "9"), ("ɶ", "&"),
+  ("ɵ", "8"), ("ɤ", "7"), ("u", "u"), ("ʊ", "U"), ("ʉ", "}"), ("ɯ", "M"), ("ʌ", "V"),
+  ("y", "y"), ("ʏ", "Y"),
+  // Suprasegmentals.
+  ("ˈ", "\""), ("ˌ", "%"), ("ː", ":"), ("ˑ", ":\\"), (".", "."), ("‿", "-\\"),
+  ("‖", "||"), ("|", "|"), (" ", " "),
+  // Diacritics, and tones.
+  ("\u{0361}", "_"), ("\u{0303}", "~"), ("ʰ", "_h"), ("ʷ", "_w"), ("ʲ", "'"), ("ʲ", "_j"),
+  ("\u{0329}", "="), ("\u{032F}", "_^"), ("˞", "`"), ("\u{032A}", "_d"), ("ʼ", "_>"),
+  ("\u{0325}", "_0"), ("\u{032C}", "_v"), ("\u{0324}", "_t"), ("\u{0330}", "_k"),
+  ("\u{031A}", "_}"), ("ⁿ", "_n"), ("ˡ", "_l"), ("\u{0308}", "_\""), ("\u{033D}", "_x"),
+  ("\u{031D}", "_r"), ("\u{031E}", "_o"), ("\u{0318}", "_A"), ("\u{0319}", "_q"),
+  ("\u{031F}", "_+"), ("\u{0320}", "_-"), ("\u{031C}", "_c"), ("\u{0339}", "_O"),
+  ("\u{033A}", "_a"), ("\u{033B}", "_m"), ("\u{0334}", "_e"), ("˥", "_T"), ("˦", "_H"),
+  ("˧", "_M"), ("˨", "_L"), ("˩", "_B"),
+];
+
+/// The IPA symbols each Polly language uses, broken down into single characters. Tie bars,
+/// and spaces are always allowed. Languages missing from here are only checked against
+/// the full alphabet.
+const POLLY_LANGUAGES: &[(&str, &str)] = &[
+  ("en-US", "bdʒðfɡghjklmnŋpɹsʃtθvwzəɚæaɪʊɑeɝɛioɔuʌˈˌ."),
+  ("en-GB", "bdʒðfɡghjklmnŋpɹsʃtθvwzəɜæaɪʊɑeɛioɒɔuʌːˈˌ."),
+  ("en-AU", "bdʒðfɡghjklmnŋpɹsʃtθvwzəɜæaɪʊɑeɛioɒɔuʌɐːˈˌ."),
+];
+
+/// Splits a `ph` up into the index of each symbol in `SYMBOLS`, always taking the longest
+/// symbol that matches. Anything that isn't a symbol is handed back as an error instead.
+fn segment(ph: &str, alphabet: PhonemeAlphabet) -> Vec<(Span, Result<usize, ()>)> {
+  let mut segments = Vec::new();
+  let mut position = 0;
+
+  while position < ph.len() {
+    let rest = &ph[position..];
+    let found = SYMBOLS.iter()
+      .enumerate()
+      .map(|(index, &(ipa, x_sampa))| (index, if alphabet == PhonemeAlphabet::Ipa { ipa } else { x_sampa }))
+      .filter(|(_, symbol)| rest.starts_with(symbol))
+      .fold(None, |longest: Option<(usize, &str)>, (index, symbol)| match longest {
+        Some((_, longest_symbol)) if longest_symbol.len() >= symbol.len() => longest,
+        _ => Some((index, symbol)),
+      });
+
+    let length = match found {
+      Some((_, symbol)) => symbol.len(),
+      None => rest.chars().next().unwrap().len_utf8(),
+    };
+    segments.push((Span { start: position, end: position + length }, found.map(|(index, _)| index).ok_or(())));
+    position += length;
+  }
+
+  segments
+}
+
+/// Checks a `ph` against the symbols of it's alphabet, and if a language is given, against
+/// the symbols Polly supports for that language. Spans are byte offsets into `ph`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::phonetics::validate_ph;
+/// use text_to_polly_ssml::ssml_constants::PhonemeAlphabet;
+/// assert!(validate_ph("pɪˈkɑːn", PhonemeAlphabet::Ipa, None).is_empty());
+/// assert_eq!(validate_ph("pɪˈkɑːn", PhonemeAlphabet::Ipa, Some("en-US"))[0].message,
+///   "\"ː\" is not a phoneme Polly supports for en-US.");
+/// assert_eq!(validate_ph("pI\"k#n", PhonemeAlphabet::XSampa, None)[0].message,
+///   "\"#\" is not a valid x-sampa symbol.");
+/// ```
+pub fn validate_ph(ph: &str, alphabet: PhonemeAlphabet, lang: Option<&str>) -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+  let allowed = lang.and_then(|lang| {
+    POLLY_LANGUAGES.iter()
+      .find(|(language, _)| language.eq_ignore_ascii_case(lang))
+      .map(|&(language, allowed)| (language, allowed))
+  });
+
+  for (span, segment) in segment(ph, alphabet) {
+    let index = match segment {
+      Ok(index) => index,
+      Err(()) => {
+        diagnostics.push(Diagnostic::error(
+          format!("\"{}\" is not a valid {} symbol.", &ph[span.start..span.end], alphabet), Some(span)));
+        continue;
+      },
+    };
+
+    if let Some((language, allowed)) = allowed {
+      let ipa = SYMBOLS[index].0;
+      let is_allowed = ipa.chars().all(|character| {
+        character == '\u{0361}' || character == ' ' || allowed.contains(character)
+      });
+      if !is_allowed {
+        diagnostics.push(Diagnostic::error(
+          format!("\"{}\" is not a phoneme Polly supports for {}.", &ph[span.start..span.end], language),
+          Some(span)));
+      }
+    }
+  }
+
+  diagnostics
+}
+
+/// Converts a `ph` from one alphabet into another.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::phonetics::convert;
+/// use text_to_polly_ssml::ssml_constants::PhonemeAlphabet;
+/// let x_sampa = convert("pɪˈkɑːn", PhonemeAlphabet::Ipa, PhonemeAlphabet::XSampa).unwrap();
+/// assert_eq!(x_sampa, "pI\"kA:n");
+/// assert_eq!(convert(&x_sampa, PhonemeAlphabet::XSampa, PhonemeAlphabet::Ipa).unwrap(), "pɪˈkɑːn");
+/// ```
+pub fn convert(ph: &str, from: PhonemeAlphabet, to: PhonemeAlphabet) -> Result<String, Error> {
+  let mut converted = String::with_capacity(ph.len());
+
+  for (span, segment) in segment(ph, from) {
+    let index = segment.map_err(|_| {
+      format_err!("\"{}\" is not a valid {} symbol ({}..{})", &ph[span.start..span.end], from, span.start, span.end)
+    })?;
+    let symbol = match to {
+      PhonemeAlphabet::Ipa => {
+        let x_sampa = SYMBOLS[index].1;
+        SYMBOLS.iter().find(|&&(_, candidate)| candidate == x_sampa).unwrap().0
+      },
+      PhonemeAlphabet::XSampa => {
+        let ipa = SYMBOLS[index].0;
+        SYMBOLS.iter().find(|&&(candidate, _)| candidate == ipa).unwrap().1
+      },
+    };
+    converted.push_str(symbol);
+  }
+
+  Ok(converted)
+}
+
+/// Converts a `ph` written in IPA into X-SAMPA.
+pub fn ipa_to_x_sampa(ph: &str) -> Result<String, Error> {
+  convert(ph, PhonemeAlphabet::Ipa, PhonemeAlphabet::XSampa)
+}
+
+/// Converts a `ph` written in X-SAMPA into IPA.
+pub fn x_sampa_to_ipa(ph: &str) -> Result<String, Error> {
+  convert(ph, PhonemeAlphabet::XSampa, PhonemeAlphabet::Ipa)
+}
@@ -0,0 +1,241 @@
+//! Turns either the `${...}` markup, or SSML that has already been generated back into
+//! the plain text that will actually be spoken. Useful for things like transcripts, or
+//! a search index, where the tags are just noise.
+
+use failure::Error;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::parser::tokenize;
+use crate::ssml_constants::*;
+
+/// Options for controlling how plain text gets extracted.
+#[derive(Clone, Debug, Default)]
+pub struct PlainTextOptions {
+  /// If text inside of a `say-as` tag with an `interpret-as` of `spell-out`, or
+  /// `characters` should be expanded into it's individual letters. E.g. `abc` becomes `a b c`.
+  pub expand_spell_out: bool,
+}
+
+/// What an open tag means for the text inside of it.
+enum FrameKind {
+  /// Text inside is spoken as is.
+  Passthrough,
+  /// Text inside is replaced by the alias, which has already been written.
+  Substituted,
+  /// Text inside is spelled out letter by letter.
+  SpelledOut,
+}
+
+struct Frame {
+  tag: String,
+  kind: FrameKind,
+}
+
+/// Keeps track of the currently open tags, and writes text accordingly.
+struct PlainTextWriter<'a> {
+  options: &'a PlainTextOptions,
+  frames: Vec<Frame>,
+  output: String,
+  /// What goes between the text before, and after a paragraph, sentence, or break. It's
+  /// only written once there's more text, and only if there's no whitespace there already.
+  separator: Option<&'static str>,
+}
+
+impl<'a> PlainTextWriter<'a> {
+
+  fn new(options: &'a PlainTextOptions) -> PlainTextWriter<'a> {
+    PlainTextWriter {
+      options,
+      frames: Vec::new(),
+      output: String::new(),
+      separator: None,
+    }
+  }
+
+  fn start(&mut self, tag: &str, alias: Option<&str>, interpret_as: Option<&str>) {
+    self.separate(tag);
+    let kind = match (tag.parse::<PossibleOpenTags>(), alias) {
+      (Ok(PossibleOpenTags::Sub), Some(alias)) => {
+        if !self.is_substituted() {
+          self.write(alias);
+        }
+        FrameKind::Substituted
+      },
+      (Ok(PossibleOpenTags::SayAs), _) if self.options.expand_spell_out => {
+        match interpret_as.map(|value| value.to_lowercase()) {
+          Some(ref value) if value == "spell-out" || value == "characters" => FrameKind::SpelledOut,
+          _ => FrameKind::Passthrough,
+        }
+      },
+      _ => FrameKind::Passthrough,
+    };
+    self.frames.push(Frame {
+      tag: tag.to_lowercase(),
+      kind,
+    });
+  }
+
+  fn end(&mut self, tag: &str) {
+    self.separate(tag);
+    let tag = tag.to_lowercase();
+    if let Some(position) = self.frames.iter().rposition(|frame| frame.tag == tag) {
+      self.frames.remove(position);
+    }
+  }
+
+  /// Paragraphs go on a line of their own, while sentences, and breaks are kept apart by
+  /// a space.
+  fn separate(&mut self, tag: &str) {
+    match tag.parse::<PossibleOpenTags>() {
+      Ok(PossibleOpenTags::Paragraph) => self.separator = Some("\n"),
+      Ok(PossibleOpenTags::Sentence) | Ok(PossibleOpenTags::Break) => {
+        self.separator = self.separator.or(Some(" "));
+      },
+      _ => {},
+    };
+  }
+
+  fn write(&mut self, text: &str) {
+    if text.is_empty() {
+      return;
+    }
+    if let Some(separator) = self.separator.take() {
+      let spaced = self.output.ends_with(char::is_whitespace) || text.starts_with(char::is_whitespace);
+      if !self.output.is_empty() && !spaced {
+        self.output.push_str(separator);
+      }
+    }
+    self.output.push_str(text);
+  }
+
+  fn is_substituted(&self) -> bool {
+    self.frames.iter().any(|frame| matches!(frame.kind, FrameKind::Substituted))
+  }
+
+  fn is_spelled_out(&self) -> bool {
+    self.frames.iter().any(|frame| matches!(frame.kind, FrameKind::SpelledOut))
+  }
+
+  fn text(&mut self, text: &str) {
+    if self.is_substituted() {
+      return;
+    }
+    if !self.is_spelled_out() {
+      self.write(text);
+      return;
+    }
+
+    let trimmed = text.trim();
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    let letters = trimmed.chars()
+      .filter(|character| !character.is_whitespace())
+      .map(|character| character.to_string())
+      .collect::<Vec<String>>()
+      .join(" ");
+    let mut spelled_out = format!("{}{}", leading, letters);
+    if !trimmed.is_empty() {
+      spelled_out.push_str(trailing);
+    }
+    self.write(&spelled_out);
+  }
+
+}
+
+/// Extracts the spoken text out of some `${...}` markup. Tags are dropped, `sub` tags are
+/// replaced by their alias, and `break`/`amazon:breath` tags produce nothing at all. Words
+/// are kept apart across paragraphs (with a new line), sentences, and breaks (with a space).
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::plain_text::{markup_to_plain_text, PlainTextOptions};
+/// let options = PlainTextOptions { expand_spell_out: true };
+/// let result = markup_to_plain_text(
+///   "${sub|alias=mercury}hg${/sub} is ${say-as|interpret-as=spell-out}abc${/say-as}.${break}",
+///   &options,
+/// );
+/// assert_eq!(result.unwrap(), "mercury is a b c.");
+/// ```
+pub fn markup_to_plain_text(data: &str, options: &PlainTextOptions) -> Result<String, Error> {
+  let mut writer = PlainTextWriter::new(options);
+
+  for item in tokenize(data)? {
+    if let Some(ref start_tag) = item.start_tag {
+      writer.start(
+        &start_tag.tag_key,
+        start_tag.params.get("alias").map(String::as_str),
+        start_tag.params.get("interpret-as").map(String::as_str),
+      );
+    }
+    if let Some(ref end_tag) = item.end_tag {
+      writer.end(&end_tag.tag_key);
+    }
+    if let Some(ref data) = item.data {
+      writer.text(&data.replace("$\\{", "${"));
+    }
+  }
+
+  Ok(writer.output)
+}
+
+/// Extracts the spoken text out of an SSML document, such as one generated by
+/// `parse_string`. Follows the same rules as `markup_to_plain_text`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::plain_text::{ssml_to_plain_text, PlainTextOptions};
+/// let result = ssml_to_plain_text(
+///   r#"<?xml version="1.0"?><speak><sub alias="mercury">hg</sub> &amp; <break/>more</speak>"#,
+///   &PlainTextOptions::default(),
+/// );
+/// assert_eq!(result.unwrap(), "mercury & more");
+/// ```
+pub fn ssml_to_plain_text(ssml: &str, options: &PlainTextOptions) -> Result<String, Error> {
+  let mut writer = PlainTextWriter::new(options);
+  let mut reader = Reader::from_str(ssml);
+  let mut buf = Vec::new();
+
+  loop {
+    match reader.read_event(&mut buf)? {
+      Event::Start(ref elem) => {
+        let tag = reader.decode(elem.name()).into_owned();
+        let mut alias = None;
+        let mut interpret_as = None;
+        for attribute in elem.attributes() {
+          let attribute = attribute?;
+          match attribute.key {
+            b"alias" => alias = Some(attribute.unescape_and_decode_value(&reader)?),
+            b"interpret-as" => interpret_as = Some(attribute.unescape_and_decode_value(&reader)?),
+            _ => {},
+          };
+        }
+        writer.start(&tag, alias.as_deref(), interpret_as.as_deref());
+      },
+      Event::End(ref elem) => {
+        let tag = reader.decode(elem.name()).into_owned();
+        writer.end(&tag);
+      },
+      Event::Empty(ref elem) => {
+        let tag = reader.decode(elem.name()).into_owned();
+        writer.start(&tag, None, None);
+        writer.end(&tag);
+      },
+      Event::Text(ref text) => {
+        let text = text.unescape_and_decode(&reader)?;
+        writer.text(&text);
+      },
+      Event::CData(ref text) => {
+        let text = reader.decode(text.escaped()).into_owned();
+        writer.text(&text);
+      },
+      Event::Eof => break,
+      _ => {},
+    };
+    buf.clear();
+  }
+
+  Ok(writer.output)
+}
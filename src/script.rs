@@ -0,0 +1,266 @@
+//! Turns a dialogue script into SSML, one document per line of dialogue. Each line starts
+//! with the speaker's name in capitals, followed by a colon:
+//!
+//! ```text
+//! ALICE: Hello ${break|time=300ms} BOB: Hi
+//! CAROL: It's been a while.
+//! ```
+//!
+//! A line keeps going until the next speaker's name, even across line breaks, and the
+//! markup inside of each line is parsed as usual. Which voice each speaker uses, along with
+//! any prosody to say their lines with comes from a `ScriptConfig`, which can be loaded
+//! from TOML (with the `toml` feature), or JSON (with the `json` feature):
+//!
+//! ```toml
+//! engine = "neural"
+//!
+//! [speakers.ALICE]
+//! voice = "Joanna"
+//!
+//! [speakers.BOB]
+//! voice = "Matthew"
+//! rate = "slow"
+//! pitch = "-10%"
+//! ```
+
+use failure::{Error, format_err};
+use regex::Regex;
+
+use std::collections::BTreeMap;
+#[cfg(any(feature = "json", feature = "toml"))]
+use std::fs;
+#[cfg(any(feature = "json", feature = "toml"))]
+use std::path::Path;
+
+use crate::diagnostics::Diagnostic;
+use crate::dialect::PollyDialect;
+use crate::parser::{prepare_tokens, write_tokens, OneItem, ParseOptions, Span};
+use crate::ssml_constants::*;
+use crate::voice::VoiceId;
+
+/// A speaker's name, in capitals, followed by a colon (and whitespace, which is checked
+/// separately so it can also come before the next speaker's name).
+const SPEAKER_LABEL: &str = r"(?:^|\s)([A-Z][A-Z0-9_]*(?:[ -][A-Z][A-Z0-9_]*)*):";
+
+/// How a speaker's lines are said.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Speaker {
+  /// The voice the speaker's lines are said with.
+  pub voice: VoiceId,
+  /// The volume of the speaker's lines, e.g. `+6dB`, or `soft`.
+  pub volume: Option<String>,
+  /// How fast the speaker talks.
+  pub rate: Option<ProsodyRate>,
+  /// The pitch of the speaker's lines, e.g. `-10%`, or `high`.
+  pub pitch: Option<String>,
+}
+
+impl Speaker {
+
+  /// A speaker using `voice`, without any prosody.
+  pub fn new(voice: &str) -> Speaker {
+    Speaker {
+      voice: VoiceId::new(voice),
+      volume: None,
+      rate: None,
+      pitch: None,
+    }
+  }
+
+  /// The `${prosody}` tag every one of the speaker's lines is wrapped in, if they have any.
+  fn prosody(&self) -> Option<OneItem> {
+    let mut params = BTreeMap::new();
+    if let Some(ref volume) = self.volume {
+      params.insert("volume".to_owned(), volume.to_owned());
+    }
+    if let Some(rate) = self.rate {
+      params.insert("rate".to_owned(), rate.to_string());
+    }
+    if let Some(ref pitch) = self.pitch {
+      params.insert("pitch".to_owned(), pitch.to_owned());
+    }
+    if params.is_empty() {
+      None
+    } else {
+      Some(OneItem::start("prosody", params))
+    }
+  }
+
+}
+
+/// Every speaker in a script, along with the Polly engine to write SSML for.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScriptConfig {
+  /// The engine the voices use.
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub engine: PollyEngine,
+  /// Each speaker, by the name used in the script.
+  pub speakers: BTreeMap<String, Speaker>,
+}
+
+impl ScriptConfig {
+
+  /// Loads a config from TOML.
+  #[cfg(feature = "toml")]
+  pub fn from_toml(data: &str) -> Result<ScriptConfig, Error> {
+    Ok(toml::from_str(data)?)
+  }
+
+  /// Loads a config from JSON, laid out the same way as the TOML.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::script::ScriptConfig;
+  /// let config = ScriptConfig::from_json(
+  ///   r#"{"speakers": {"ALICE": {"voice": "Joanna", "rate": "slow"}}}"#).unwrap();
+  /// assert_eq!(config.speakers["ALICE"].voice.as_str(), "Joanna");
+  /// ```
+  #[cfg(feature = "json")]
+  pub fn from_json(data: &str) -> Result<ScriptConfig, Error> {
+    Ok(serde_json::from_str(data)?)
+  }
+
+  /// Loads a config from a file, as TOML, or JSON depending on it's extension.
+  #[cfg(any(feature = "json", feature = "toml"))]
+  pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ScriptConfig, Error> {
+    let path = path.as_ref();
+    let data = fs::read_to_string(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+      #[cfg(feature = "toml")]
+      Some("toml") => ScriptConfig::from_toml(&data),
+      #[cfg(feature = "json")]
+      Some("json") => ScriptConfig::from_json(&data),
+      _ => Err(format_err!("Don't know how to load a script config from: {}", path.display())),
+    }
+  }
+
+  /// Adds a speaker, replacing any with the same name.
+  pub fn insert(&mut self, name: &str, speaker: Speaker) {
+    self.speakers.insert(name.to_owned(), speaker);
+  }
+
+  /// Finds a speaker by name, ignoring case.
+  pub fn lookup(&self, name: &str) -> Option<&Speaker> {
+    self.speakers.get(name).or_else(|| self.speakers.iter()
+      .find(|(speaker, _)| speaker.eq_ignore_ascii_case(name))
+      .map(|(_, speaker)| speaker))
+  }
+
+}
+
+/// A single line of dialogue.
+#[derive(Clone, Debug)]
+pub struct ScriptLine {
+  /// Who says the line, as written in the script.
+  pub speaker: String,
+  /// The voice to synthesize the SSML with.
+  pub voice: VoiceId,
+  /// The generated SSML.
+  pub ssml: String,
+  /// Every warning produced while parsing the line, with spans into the whole script.
+  pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Parses a dialogue script, handing back the SSML for each line in order. Only the names
+/// of speakers in `config` (ignoring case) start a line, anything else that looks like one
+/// (`FAQ:`) is kept as text. Text before the first speaker fails parsing, and each line's
+/// markup is parsed with `options`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::ParseOptions;
+/// use text_to_polly_ssml::script::{parse_script, ScriptConfig, Speaker};
+/// let mut config = ScriptConfig::default();
+/// config.insert("ALICE", Speaker::new("Joanna"));
+/// config.insert("BOB", Speaker::new("Matthew"));
+/// let lines = parse_script("ALICE: Hello ${break|time=300ms} BOB: Hi", &config, &ParseOptions::default()).unwrap();
+/// assert_eq!(lines.len(), 2);
+/// assert_eq!(lines[0].voice.as_str(), "Joanna");
+/// assert!(lines[0].ssml.ends_with(r#">Hello <break time="300ms"/></speak>"#));
+/// assert!(lines[1].ssml.ends_with(">Hi</speak>"));
+/// ```
+pub fn parse_script(script: &str, config: &ScriptConfig, options: &ParseOptions) -> Result<Vec<ScriptLine>, Error> {
+  let label = Regex::new(SPEAKER_LABEL).unwrap();
+  let labels = label.captures_iter(script)
+    .filter_map(|captures| Some((captures.get(1)?, captures.get(0)?.end())))
+    .filter(|(_, end)| script[*end..].chars().next().map_or(true, char::is_whitespace))
+    .filter(|(name, _)| !inside_tag(&script[..name.start()]))
+    .filter_map(|(name, end)| Some((find_speaker(name, config)?, end)))
+    .collect::<Vec<_>>();
+
+  let first = labels.first().map(|(name_start, _)| *name_start).unwrap_or(script.len());
+  if !script[..first].trim().is_empty() {
+    let unknown = label.captures(script).and_then(|captures| captures.get(1))
+      .filter(|name| script[..name.start()].trim().is_empty());
+    return Err(match unknown {
+      Some(name) => format_err!("\"{}\" isn't one of the speakers. ({}..{})", name.as_str(), name.start(), name.end() + 1),
+      None => format_err!("The script has to start with a speaker, like \"ALICE:\". (0..{})", first),
+    });
+  }
+
+  let mut lines = Vec::with_capacity(labels.len());
+  for (index, (name_start, text_start)) in labels.iter().enumerate() {
+    let text_end = labels.get(index + 1).map(|(next, _)| *next).unwrap_or(script.len());
+    let span = Span { start: *name_start, end: *text_start };
+    let name = &script[*name_start..*text_start - 1];
+    let speaker = config.lookup(name).ok_or_else(|| format_err!(
+      "\"{}\" isn't one of the speakers. ({}..{})", name, span.start, span.end))?;
+
+    let text = &script[*text_start..text_end];
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+      continue;
+    }
+    let offset = *text_start + (text.len() - text.trim_start().len());
+
+    let mut diagnostics = Vec::new();
+    let mut items = prepare_tokens(trimmed, options, &mut diagnostics)?;
+    for (item_span, _) in &mut items {
+      item_span.start += offset;
+      item_span.end += offset;
+    }
+    for diagnostic in &mut diagnostics {
+      diagnostic.span = diagnostic.span.map(|diagnostic_span| Span {
+        start: diagnostic_span.start + offset,
+        end: diagnostic_span.end + offset,
+      });
+    }
+    if let Some(prosody) = speaker.prosody() {
+      items.insert(0, (span, prosody));
+      items.push((span, OneItem::end("prosody")));
+    }
+
+    let ssml = write_tokens(&items, options, PollyDialect::new(config.engine), &mut diagnostics)?;
+    lines.push(ScriptLine {
+      speaker: name.to_owned(),
+      voice: speaker.voice.clone(),
+      ssml,
+      diagnostics,
+    });
+  }
+
+  Ok(lines)
+}
+
+/// Where the name of a speaker in `config` starts in a label, which can have other words
+/// in capitals before it (`SAY HI BOB:`). `None` if the label isn't a speaker at all, such
+/// as `FAQ:` in the middle of a line.
+fn find_speaker(label: regex::Match, config: &ScriptConfig) -> Option<usize> {
+  let name = label.as_str();
+  std::iter::once(0).chain(name.match_indices(' ').map(|(space, _)| space + 1))
+    .find(|start| config.lookup(&name[*start..]).is_some())
+    .map(|start| label.start() + start)
+}
+
+/// If the end of `text` is inside of a `${...}` tag.
+fn inside_tag(text: &str) -> bool {
+  match (text.rfind("${"), text.rfind('}')) {
+    (Some(open), Some(close)) => open > close,
+    (Some(_), None) => true,
+    _ => false,
+  }
+}
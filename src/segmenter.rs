@@ -0,0 +1,214 @@
+//! Splits plain text up into paragraphs, and sentences. Blocks of text separated by a blank
+//! line are wrapped in `<p>`, and each sentence inside of them in `<s>`, which gives Polly
+//! a much better idea of where to pause than one giant run of text.
+//!
+//! Only text outside of any tag is touched. Anything inside of a tag is kept together in
+//! the sentence it starts in, and text the user already wrapped in `${p}`, or `${s}` is
+//! left exactly as is.
+
+use std::collections::BTreeMap;
+
+use crate::document::is_empty_tag;
+use crate::parser::{OneItem, Span};
+use crate::ssml_constants::*;
+
+/// Words that are almost always followed by a period, without ending the sentence.
+const ABBREVIATIONS: &[&str] = &[
+  "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "mt", "vs", "inc", "ltd", "co", "corp",
+  "jan", "feb", "mar", "apr", "jun", "jul", "aug", "sep", "sept", "oct", "nov", "dec", "ave",
+  "blvd", "rd", "gen", "gov", "sen", "rep", "capt", "lt", "col", "sgt", "fig", "vol", "approx",
+];
+
+/// Punctuation that can close a sentence.
+const SENTENCE_ENDINGS: &[char] = &['.', '!', '?', '…'];
+
+/// Punctuation that can come after the end of a sentence, but is still part of it.
+const CLOSERS: &[char] = &['"', '\'', ')', ']', '”', '’'];
+
+/// Punctuation that can come before a word.
+const OPENERS: &[char] = &['"', '\'', '(', '[', '“', '‘'];
+
+struct Segmenter {
+  output: Vec<(Span, OneItem)>,
+  open: Vec<String>,
+  in_paragraph: bool,
+  in_sentence: bool,
+  ended_sentence: bool,
+}
+
+impl Segmenter {
+
+  fn push_tag(&mut self, at: usize, item: OneItem) {
+    self.output.push((Span { start: at, end: at }, item));
+  }
+
+  fn start_content(&mut self, at: usize) {
+    if !self.in_paragraph {
+      self.push_tag(at, OneItem::start(&PossibleOpenTags::Paragraph.to_string(), BTreeMap::new()));
+      self.in_paragraph = true;
+    }
+    if !self.in_sentence {
+      self.push_tag(at, OneItem::start(&PossibleOpenTags::Sentence.to_string(), BTreeMap::new()));
+      self.in_sentence = true;
+    }
+  }
+
+  fn close_sentence(&mut self, at: usize) {
+    if self.in_sentence {
+      self.push_tag(at, OneItem::end(&PossibleClosingTags::Sentence.to_string()));
+      self.in_sentence = false;
+    }
+    self.ended_sentence = false;
+  }
+
+  fn close_paragraph(&mut self, at: usize) {
+    self.close_sentence(at);
+    if self.in_paragraph {
+      self.push_tag(at, OneItem::end(&PossibleClosingTags::Paragraph.to_string()));
+      self.in_paragraph = false;
+    }
+  }
+
+  fn push_text(&mut self, offset: usize, text: &str, start: usize, end: usize) {
+    if start == end {
+      return;
+    }
+    if !text[start..end].trim().is_empty() {
+      self.start_content(offset + start);
+    }
+    self.output.push((Span { start: offset + start, end: offset + end }, OneItem::text(&text[start..end])));
+  }
+
+  fn start_tag(&mut self, span: Span, item: OneItem) {
+    let start_tag = item.start_tag.as_ref().unwrap();
+    let name = start_tag.tag_key.to_lowercase();
+    if let Ok(tag) = start_tag.tag_key.parse::<PossibleOpenTags>() {
+      if self.open.is_empty() {
+        match tag {
+          PossibleOpenTags::Paragraph => self.close_paragraph(span.start),
+          PossibleOpenTags::Sentence => self.close_sentence(span.start),
+          _ => self.start_content(span.start),
+        };
+      }
+      if !is_empty_tag(&name) {
+        self.open.push(name);
+      }
+    }
+    self.output.push((span, item));
+  }
+
+  fn end_tag(&mut self, span: Span, item: OneItem) {
+    let name = item.end_tag.as_ref().unwrap().tag_key.to_lowercase();
+    if let Some(position) = self.open.iter().rposition(|open| *open == name) {
+      self.open.truncate(position);
+    }
+    self.output.push((span, item));
+  }
+
+  fn text(&mut self, span: Span, text: &str) {
+    if !self.open.is_empty() {
+      if !text.trim().is_empty() {
+        self.ended_sentence = ends_sentence(text.trim_end());
+      }
+      self.output.push((span, OneItem::text(text)));
+      return;
+    }
+
+    let mut segment_start = 0;
+    let mut characters = text.char_indices().peekable();
+    while let Some((run_start, character)) = characters.next() {
+      if !character.is_whitespace() {
+        continue;
+      }
+      let mut run_end = run_start + character.len_utf8();
+      let mut newlines = if character == '\n' { 1 } else { 0 };
+      while let Some(&(index, next)) = characters.peek() {
+        if !next.is_whitespace() {
+          break;
+        }
+        if next == '\n' {
+          newlines += 1;
+        }
+        run_end = index + next.len_utf8();
+        characters.next();
+      }
+
+      // A sentence carries on when the next word starts lowercase, like `"Yes!" she said.`
+      let continues = text[run_end..].trim_start_matches(OPENERS).chars().next().is_some_and(char::is_lowercase);
+      let ends = !continues && if run_start == 0 { self.ended_sentence } else { ends_sentence(&text[..run_start]) };
+      if newlines >= 2 {
+        self.push_text(span.start, text, segment_start, run_start);
+        self.close_paragraph(span.start + run_start);
+      } else if ends {
+        self.push_text(span.start, text, segment_start, run_start);
+        self.close_sentence(span.start + run_start);
+      } else if segment_start < run_start || self.in_sentence {
+        continue;
+      }
+      self.push_text(span.start, text, run_start, run_end);
+      segment_start = run_end;
+    }
+
+    self.push_text(span.start, text, segment_start, text.len());
+    self.ended_sentence = segment_start < text.len() && ends_sentence(text);
+  }
+
+}
+
+/// If `text` ends with the end of a sentence.
+fn ends_sentence(text: &str) -> bool {
+  let trimmed = text.trim_end_matches(CLOSERS);
+  let last = match trimmed.chars().last() {
+    Some(last) if SENTENCE_ENDINGS.contains(&last) => last,
+    _ => return false,
+  };
+  if last != '.' {
+    return true;
+  }
+
+  let before = &trimmed[..trimmed.len() - 1];
+  let word = before.rsplit(char::is_whitespace).next().unwrap_or("").trim_start_matches(OPENERS);
+  if word.is_empty() || word.ends_with('.') {
+    return true;
+  }
+  let is_initial = word.chars().count() == 1 && word.chars().all(char::is_uppercase);
+  let is_dotted = word.contains('.') && word.split('.').all(|part| part.chars().all(char::is_alphabetic));
+  !(is_initial || is_dotted || ABBREVIATIONS.contains(&&*word.to_lowercase()))
+}
+
+/// Wraps paragraphs, and sentences in the text tokens in `p`, and `s` tags.
+pub(crate) fn segment(items: Vec<(Span, OneItem)>) -> Vec<(Span, OneItem)> {
+  let end = items.last().map_or(0, |(span, _)| span.end);
+  let mut segmenter = Segmenter {
+    output: Vec::with_capacity(items.len()),
+    open: Vec::new(),
+    in_paragraph: false,
+    in_sentence: false,
+    ended_sentence: false,
+  };
+
+  for (span, item) in items {
+    if item.start_tag.is_some() {
+      segmenter.start_tag(span, item);
+    } else if item.end_tag.is_some() {
+      segmenter.end_tag(span, item);
+    } else if let Some(ref text) = item.data {
+      segmenter.text(span, text);
+    }
+  }
+
+  // Trailing whitespace belongs after the last paragraph, not inside of it.
+  let mut trailing = Vec::new();
+  while let Some((span, item)) = segmenter.output.pop() {
+    if item.data.as_ref().is_some_and(|text| text.trim().is_empty()) {
+      trailing.push((span, item));
+    } else {
+      segmenter.output.push((span, item));
+      break;
+    }
+  }
+  let end = trailing.last().map_or(end, |(span, _)| span.start);
+  segmenter.close_paragraph(end);
+  segmenter.output.extend(trailing.into_iter().rev());
+  segmenter.output
+}
@@ -9,6 +9,7 @@ use std::str::FromStr;
 /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_break),
 /// and what they actually do in polly is documented:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#break-tag).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BreakStrength {
   NoStrength,
   XWeak,
@@ -18,15 +19,52 @@ pub enum BreakStrength {
   XStrong,
 }
 
+impl BreakStrength {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = BreakStrength> {
+    const VARIANTS: &[BreakStrength] = &[
+      BreakStrength::NoStrength,
+      BreakStrength::XWeak,
+      BreakStrength::Weak,
+      BreakStrength::Medium,
+      BreakStrength::Strong,
+      BreakStrength::XStrong,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+  /// Roughly how long Polly pauses for a break of this strength. Polly treats `x-weak` the
+  /// same as `none`, and `medium` the same as `weak` (a comma), with `strong` being the end
+  /// of a sentence, and `x-strong` the end of a paragraph. These are estimates, as the
+  /// actual pause depends on the voice, and speaking rate.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::ssml_constants::{BreakStrength, BreakTime};
+  /// assert_eq!(BreakStrength::Strong.nominal_duration(), BreakTime::from_milliseconds(500));
+  /// ```
+  pub fn nominal_duration(self) -> BreakTime {
+    match self {
+      BreakStrength::NoStrength | BreakStrength::XWeak => BreakTime::from_milliseconds(0),
+      BreakStrength::Weak | BreakStrength::Medium => BreakTime::from_milliseconds(250),
+      BreakStrength::Strong => BreakTime::from_milliseconds(500),
+      BreakStrength::XStrong => BreakTime::from_milliseconds(1000),
+    }
+  }
+
+}
+
 impl fmt::Display for BreakStrength {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
-      &BreakStrength::NoStrength => write!(f, "none"),
-      &BreakStrength::XWeak => write!(f, "x-weak"),
-      &BreakStrength::Weak => write!(f, "weak"),
-      &BreakStrength::Medium => write!(f, "medium"),
-      &BreakStrength::Strong => write!(f, "strong"),
-      &BreakStrength::XStrong => write!(f, "x-strong"),
+    match *self {
+      BreakStrength::NoStrength => write!(f, "none"),
+      BreakStrength::XWeak => write!(f, "x-weak"),
+      BreakStrength::Weak => write!(f, "weak"),
+      BreakStrength::Medium => write!(f, "medium"),
+      BreakStrength::Strong => write!(f, "strong"),
+      BreakStrength::XStrong => write!(f, "x-strong"),
     }
   }
 }
@@ -36,7 +74,7 @@ impl FromStr for BreakStrength {
 
     fn from_str(s: &str) -> Result<BreakStrength, ()> {
       match &*s.to_lowercase() {
-        "break" => Ok(BreakStrength::NoStrength),
+        "none" => Ok(BreakStrength::NoStrength),
         "x-weak" => Ok(BreakStrength::XWeak),
         "weak" => Ok(BreakStrength::Weak),
         "medium" => Ok(BreakStrength::Medium),
@@ -52,16 +90,18 @@ impl FromStr for BreakStrength {
 /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_break)
 /// and what they actually do in polly is documented:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#break-tag).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct BreakTime {
-  /// The actual value of time to break for.
-  pub time: u32,
-  /// If the break time is in seconds. If this is set to false it is believed to be in
-  /// milliseconds.
-  pub is_seconds: bool,
+  /// The amount of time to break for, always stored in milliseconds so `1s` and `1000ms`
+  /// are the same break.
+  milliseconds: u32,
 }
 
 impl BreakTime {
 
+  /// The longest break Polly will actually pause for, anything longer is cut down to this.
+  pub const POLLY_MAX: BreakTime = BreakTime { milliseconds: 10_000 };
+
   /// Constructs a new Break Time.
   ///
   /// # Examples
@@ -72,40 +112,72 @@ impl BreakTime {
   /// let other_break_time = BreakTime::new(5, false);
   /// ```
   pub fn new(value: u32, is_seconds: bool) -> BreakTime {
+    if is_seconds {
+      BreakTime::from_milliseconds(value.saturating_mul(1000))
+    } else {
+      BreakTime::from_milliseconds(value)
+    }
+  }
+
+  /// Constructs a new Break Time from a number of milliseconds.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::ssml_constants::BreakTime;
+  /// assert_eq!(BreakTime::from_milliseconds(1500).to_string(), "1500ms");
+  /// assert_eq!(BreakTime::from_milliseconds(2000), BreakTime::new(2, true));
+  /// ```
+  pub fn from_milliseconds(milliseconds: u32) -> BreakTime {
     BreakTime {
-      time: value,
-      is_seconds: is_seconds,
+      milliseconds,
     }
   }
 
+  /// The length of the break in milliseconds.
+  pub fn as_milliseconds(self) -> u32 {
+    self.milliseconds
+  }
+
 }
 
 impl fmt::Display for BreakTime {
+  /// Whole seconds are written in seconds (`2s`), everything else in milliseconds (`1500ms`).
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    write!(f, "{}{}", self.time, if self.is_seconds { "s" } else { "ms" })
+    if self.milliseconds % 1000 == 0 {
+      write!(f, "{}s", self.milliseconds / 1000)
+    } else {
+      write!(f, "{}ms", self.milliseconds)
+    }
   }
 }
 
 impl FromStr for BreakTime {
     type Err = ();
 
+    /// Parses either seconds, or milliseconds, which can both have a decimal part (`1.5s`).
+    /// Anything finer than a millisecond is rounded.
     fn from_str(s: &str) -> Result<BreakTime, ()> {
-      if s.ends_with("ms") && s != "ms" {
-        let mut as_split = s.split("ms");
-        let potential_number = as_split.next().unwrap();
-        let as_num = potential_number.parse::<u32>();
-        if as_num.is_ok() {
-          return Ok(BreakTime::new(as_num.unwrap(), false))
-        }
-      } else if s.ends_with("s") && s != "s" {
-        let mut as_split = s.split("s");
-        let potential_number = as_split.next().unwrap();
-        let as_num = potential_number.parse::<u32>();
-        if as_num.is_ok() {
-          return Ok(BreakTime::new(as_num.unwrap(), true))
-        }
+      let (number, multiplier) = if let Some(number) = s.strip_suffix("ms") {
+        (number, 1.0)
+      } else if let Some(number) = s.strip_suffix('s') {
+        (number, 1000.0)
+      } else {
+        return Err(());
+      };
+
+      let is_number = number.chars().any(|character| character.is_ascii_digit()) &&
+        number.chars().all(|character| character.is_ascii_digit() || character == '.') &&
+        number.matches('.').count() <= 1;
+      if !is_number {
+        return Err(());
       }
-      return Err(())
+
+      let milliseconds = (number.parse::<f64>().map_err(|_| ())? * multiplier).round();
+      if milliseconds > f64::from(u32::MAX) {
+        return Err(());
+      }
+      Ok(BreakTime::from_milliseconds(milliseconds as u32))
     }
 }
 
@@ -113,16 +185,30 @@ impl FromStr for BreakTime {
 /// Documentation on supported alphabets can be found under description of the phoneme
 /// tags on AWS Polly. Those are located:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#phoneme-tag)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PhonemeAlphabet {
   Ipa,
   XSampa,
 }
 
+impl PhonemeAlphabet {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = PhonemeAlphabet> {
+    const VARIANTS: &[PhonemeAlphabet] = &[
+      PhonemeAlphabet::Ipa,
+      PhonemeAlphabet::XSampa,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
 impl fmt::Display for PhonemeAlphabet {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
-      &PhonemeAlphabet::Ipa => write!(f, "ipa"),
-      &PhonemeAlphabet::XSampa => write!(f, "x-sampa"),
+    match *self {
+      PhonemeAlphabet::Ipa => write!(f, "ipa"),
+      PhonemeAlphabet::XSampa => write!(f, "x-sampa"),
     }
   }
 }
@@ -142,6 +228,7 @@ impl FromStr for PhonemeAlphabet {
 /// Represents all possible ProsodyRate rates that AWS Polly Supports.
 /// The full documentation on all possible rates are found in AWS Documentation:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#prosody-tag)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ProsodyRate {
   XSlow,
   Slow,
@@ -150,14 +237,30 @@ pub enum ProsodyRate {
   XFast,
 }
 
+impl ProsodyRate {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = ProsodyRate> {
+    const VARIANTS: &[ProsodyRate] = &[
+      ProsodyRate::XSlow,
+      ProsodyRate::Slow,
+      ProsodyRate::Medium,
+      ProsodyRate::Fast,
+      ProsodyRate::XFast,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
 impl fmt::Display for ProsodyRate {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
-      &ProsodyRate::XSlow => write!(f, "x-slow"),
-      &ProsodyRate::Slow => write!(f, "slow"),
-      &ProsodyRate::Medium => write!(f, "medium"),
-      &ProsodyRate::Fast => write!(f, "fast"),
-      &ProsodyRate::XFast => write!(f, "x-fast"),
+    match *self {
+      ProsodyRate::XSlow => write!(f, "x-slow"),
+      ProsodyRate::Slow => write!(f, "slow"),
+      ProsodyRate::Medium => write!(f, "medium"),
+      ProsodyRate::Fast => write!(f, "fast"),
+      ProsodyRate::XFast => write!(f, "x-fast"),
     }
   }
 }
@@ -180,18 +283,33 @@ impl FromStr for ProsodyRate {
 /// Represents all possible WorldRoles that AWS Polly Supports.
 /// The full documentation on all possible world roles are found in AWS docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#w-tag)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum WordRole {
   Verb,
   PastTense,
   PresentTense,
 }
 
+impl WordRole {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = WordRole> {
+    const VARIANTS: &[WordRole] = &[
+      WordRole::Verb,
+      WordRole::PastTense,
+      WordRole::PresentTense,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
 impl fmt::Display for WordRole {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
-      &WordRole::Verb => write!(f, "amazon:VB"),
-      &WordRole::PastTense => write!(f, "amazon:VBD"),
-      &WordRole::PresentTense => write!(f, "amazon:SENSE_1"),
+    match *self {
+      WordRole::Verb => write!(f, "amazon:VB"),
+      WordRole::PastTense => write!(f, "amazon:VBD"),
+      WordRole::PresentTense => write!(f, "amazon:SENSE_1"),
     }
   }
 }
@@ -212,16 +330,30 @@ impl FromStr for WordRole {
 /// Represents all possible AWS Effects that AWS Polly Supports THAT DO NOT HAVE VALUES.
 /// The full documentation on all possible amazon effects are in the AWS docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum AmazonEffect {
   Whispered,
   Drc,
 }
 
+impl AmazonEffect {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = AmazonEffect> {
+    const VARIANTS: &[AmazonEffect] = &[
+      AmazonEffect::Whispered,
+      AmazonEffect::Drc,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
 impl fmt::Display for AmazonEffect {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
-      &AmazonEffect::Whispered => write!(f, "whispered"),
-      &AmazonEffect::Drc => write!(f, "drc"),
+    match *self {
+      AmazonEffect::Whispered => write!(f, "whispered"),
+      AmazonEffect::Drc => write!(f, "drc"),
     }
   }
 }
@@ -238,9 +370,148 @@ impl FromStr for AmazonEffect {
     }
 }
 
+/// Denotes the potential values for the Level of an Emphasis tag. These values are straight
+/// out of the SSML 1.1 W3C Standard which can be found
+/// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_emphasis),
+/// and what they do in polly is documented:
+/// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#emphasis-tag).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EmphasisLevel {
+  Strong,
+  Moderate,
+  Reduced,
+  NoEmphasis,
+}
+
+impl EmphasisLevel {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = EmphasisLevel> {
+    const VARIANTS: &[EmphasisLevel] = &[
+      EmphasisLevel::Strong,
+      EmphasisLevel::Moderate,
+      EmphasisLevel::Reduced,
+      EmphasisLevel::NoEmphasis,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+  /// The louder, or softer prosody (as a volume, and rate) closest to this level of
+  /// emphasis, for engines that don't support `<emphasis>`. Strong emphasis is louder, and
+  /// slower, reduced emphasis is softer, and faster, and no emphasis doesn't change anything.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::ssml_constants::{EmphasisLevel, ProsodyRate};
+  /// assert_eq!(EmphasisLevel::Strong.as_prosody(), Some(("+6dB", Some(ProsodyRate::Slow))));
+  /// assert_eq!(EmphasisLevel::NoEmphasis.as_prosody(), None);
+  /// ```
+  pub fn as_prosody(self) -> Option<(&'static str, Option<ProsodyRate>)> {
+    match self {
+      EmphasisLevel::Strong => Some(("+6dB", Some(ProsodyRate::Slow))),
+      EmphasisLevel::Moderate => Some(("+3dB", None)),
+      EmphasisLevel::Reduced => Some(("-6dB", Some(ProsodyRate::Fast))),
+      EmphasisLevel::NoEmphasis => None,
+    }
+  }
+
+}
+
+impl fmt::Display for EmphasisLevel {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      EmphasisLevel::Strong => write!(f, "strong"),
+      EmphasisLevel::Moderate => write!(f, "moderate"),
+      EmphasisLevel::Reduced => write!(f, "reduced"),
+      EmphasisLevel::NoEmphasis => write!(f, "none"),
+    }
+  }
+}
+
+impl FromStr for EmphasisLevel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<EmphasisLevel, ()> {
+      match &*s.to_lowercase() {
+        "strong" => Ok(EmphasisLevel::Strong),
+        "moderate" => Ok(EmphasisLevel::Moderate),
+        "reduced" => Ok(EmphasisLevel::Reduced),
+        "none" => Ok(EmphasisLevel::NoEmphasis),
+        _ => Err(()),
+      }
+    }
+}
+
+/// The engines Polly can synthesize speech with. Each one supports a different set of SSML
+/// tags, which are documented:
+/// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supportedtags.html).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PollyEngine {
+  #[default]
+  Standard,
+  Neural,
+  LongForm,
+  Generative,
+}
+
+impl PollyEngine {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = PollyEngine> {
+    const VARIANTS: &[PollyEngine] = &[
+      PollyEngine::Standard,
+      PollyEngine::Neural,
+      PollyEngine::LongForm,
+      PollyEngine::Generative,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+  /// If this engine supports `<emphasis>`. Only standard voices do.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::ssml_constants::PollyEngine;
+  /// assert!(PollyEngine::Standard.supports_emphasis());
+  /// assert!(!PollyEngine::Neural.supports_emphasis());
+  /// ```
+  pub fn supports_emphasis(self) -> bool {
+    self == PollyEngine::Standard
+  }
+
+}
+
+impl fmt::Display for PollyEngine {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      PollyEngine::Standard => write!(f, "standard"),
+      PollyEngine::Neural => write!(f, "neural"),
+      PollyEngine::LongForm => write!(f, "long-form"),
+      PollyEngine::Generative => write!(f, "generative"),
+    }
+  }
+}
+
+impl FromStr for PollyEngine {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<PollyEngine, ()> {
+      match &*s.to_lowercase() {
+        "standard" => Ok(PollyEngine::Standard),
+        "neural" => Ok(PollyEngine::Neural),
+        "long-form" => Ok(PollyEngine::LongForm),
+        "generative" => Ok(PollyEngine::Generative),
+        _ => Err(()),
+      }
+    }
+}
+
 /// Possible volumes of breaths for <amazon:breath>, and <amazon:auto-breaths>.
 /// The full documentation on what this does, and it's values are in AWS Docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BreathVolumes {
   Def,
   XSoft,
@@ -250,15 +521,32 @@ pub enum BreathVolumes {
   XLoud,
 }
 
+impl BreathVolumes {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = BreathVolumes> {
+    const VARIANTS: &[BreathVolumes] = &[
+      BreathVolumes::Def,
+      BreathVolumes::XSoft,
+      BreathVolumes::Soft,
+      BreathVolumes::Medium,
+      BreathVolumes::Loud,
+      BreathVolumes::XLoud,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
 impl fmt::Display for BreathVolumes {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
-      &BreathVolumes::Def => write!(f, "default"),
-      &BreathVolumes::XSoft => write!(f, "x-soft"),
-      &BreathVolumes::Soft => write!(f, "soft"),
-      &BreathVolumes::Medium => write!(f, "medium"),
-      &BreathVolumes::Loud => write!(f, "loud"),
-      &BreathVolumes::XLoud => write!(f, "x-loud"),
+    match *self {
+      BreathVolumes::Def => write!(f, "default"),
+      BreathVolumes::XSoft => write!(f, "x-soft"),
+      BreathVolumes::Soft => write!(f, "soft"),
+      BreathVolumes::Medium => write!(f, "medium"),
+      BreathVolumes::Loud => write!(f, "loud"),
+      BreathVolumes::XLoud => write!(f, "x-loud"),
     }
   }
 }
@@ -282,6 +570,7 @@ impl FromStr for BreathVolumes {
 /// Possible duration values of breaths for <amazon:breath>, and <amazon:auto-breaths>.
 /// The full documentation on what this does, and it's values are in AWS Docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BreathDuration {
   Def,
   XShort,
@@ -291,15 +580,32 @@ pub enum BreathDuration {
   XLong,
 }
 
+impl BreathDuration {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = BreathDuration> {
+    const VARIANTS: &[BreathDuration] = &[
+      BreathDuration::Def,
+      BreathDuration::XShort,
+      BreathDuration::Short,
+      BreathDuration::Medium,
+      BreathDuration::Long,
+      BreathDuration::XLong,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
 impl fmt::Display for BreathDuration {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
-      &BreathDuration::Def => write!(f, "default"),
-      &BreathDuration::XShort => write!(f, "x-short"),
-      &BreathDuration::Short => write!(f, "short"),
-      &BreathDuration::Medium => write!(f, "medium"),
-      &BreathDuration::Long => write!(f, "long"),
-      &BreathDuration::XLong => write!(f, "x-long"),
+    match *self {
+      BreathDuration::Def => write!(f, "default"),
+      BreathDuration::XShort => write!(f, "x-short"),
+      BreathDuration::Short => write!(f, "short"),
+      BreathDuration::Medium => write!(f, "medium"),
+      BreathDuration::Long => write!(f, "long"),
+      BreathDuration::XLong => write!(f, "x-long"),
     }
   }
 }
@@ -323,6 +629,7 @@ impl FromStr for BreathDuration {
 /// Possible frequency values of breaths for <amazon:auto-breaths>.
 /// The full documentation on what this does, and it's values are in AWS Docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum AutoBreathFrequency {
   Def,
   XLow,
@@ -332,15 +639,32 @@ pub enum AutoBreathFrequency {
   XHigh,
 }
 
+impl AutoBreathFrequency {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = AutoBreathFrequency> {
+    const VARIANTS: &[AutoBreathFrequency] = &[
+      AutoBreathFrequency::Def,
+      AutoBreathFrequency::XLow,
+      AutoBreathFrequency::Low,
+      AutoBreathFrequency::Medium,
+      AutoBreathFrequency::High,
+      AutoBreathFrequency::XHigh,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
 impl fmt::Display for AutoBreathFrequency {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
-      &AutoBreathFrequency::Def => write!(f, "default"),
-      &AutoBreathFrequency::XLow => write!(f, "x-low"),
-      &AutoBreathFrequency::Low => write!(f, "low"),
-      &AutoBreathFrequency::Medium => write!(f, "medium"),
-      &AutoBreathFrequency::High => write!(f, "high"),
-      &AutoBreathFrequency::XHigh => write!(f, "x-high"),
+    match *self {
+      AutoBreathFrequency::Def => write!(f, "default"),
+      AutoBreathFrequency::XLow => write!(f, "x-low"),
+      AutoBreathFrequency::Low => write!(f, "low"),
+      AutoBreathFrequency::Medium => write!(f, "medium"),
+      AutoBreathFrequency::High => write!(f, "high"),
+      AutoBreathFrequency::XHigh => write!(f, "x-high"),
     }
   }
 }
@@ -364,14 +688,27 @@ impl FromStr for AutoBreathFrequency {
 /// Represents all possible values for the `amazon:effect phonation` key.
 /// The full documentation on the supported values are in the AWS docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PhonationVolume {
   Soft,
 }
 
+impl PhonationVolume {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = PhonationVolume> {
+    const VARIANTS: &[PhonationVolume] = &[
+      PhonationVolume::Soft,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
 impl fmt::Display for PhonationVolume {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
-      &PhonationVolume::Soft => write!(f, "soft")
+    match *self {
+      PhonationVolume::Soft => write!(f, "soft")
     }
   }
 }
@@ -387,6 +724,8 @@ impl FromStr for PhonationVolume {
     }
 }
 
+/// Every tag that can be closed inside of the `${/...}` markup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PossibleClosingTags {
   LangTag,
   Mark,
@@ -397,10 +736,59 @@ pub enum PossibleClosingTags {
   SayAs,
   Sub,
   Word,
+  Emphasis,
+  Audio,
+  Voice,
   AmazonEffect,
   AmazonAutoBreaths,
 }
 
+impl PossibleClosingTags {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = PossibleClosingTags> {
+    const VARIANTS: &[PossibleClosingTags] = &[
+      PossibleClosingTags::LangTag,
+      PossibleClosingTags::Mark,
+      PossibleClosingTags::Paragraph,
+      PossibleClosingTags::Phoneme,
+      PossibleClosingTags::Prosody,
+      PossibleClosingTags::Sentence,
+      PossibleClosingTags::SayAs,
+      PossibleClosingTags::Sub,
+      PossibleClosingTags::Word,
+      PossibleClosingTags::Emphasis,
+      PossibleClosingTags::Audio,
+      PossibleClosingTags::Voice,
+      PossibleClosingTags::AmazonEffect,
+      PossibleClosingTags::AmazonAutoBreaths,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
+impl fmt::Display for PossibleClosingTags {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      PossibleClosingTags::LangTag => write!(f, "lang"),
+      PossibleClosingTags::Mark => write!(f, "mark"),
+      PossibleClosingTags::Paragraph => write!(f, "p"),
+      PossibleClosingTags::Phoneme => write!(f, "phoneme"),
+      PossibleClosingTags::Prosody => write!(f, "prosody"),
+      PossibleClosingTags::Sentence => write!(f, "s"),
+      PossibleClosingTags::SayAs => write!(f, "say-as"),
+      PossibleClosingTags::Sub => write!(f, "sub"),
+      PossibleClosingTags::Word => write!(f, "w"),
+      PossibleClosingTags::Emphasis => write!(f, "emphasis"),
+      PossibleClosingTags::Audio => write!(f, "audio"),
+      PossibleClosingTags::Voice => write!(f, "voice"),
+      PossibleClosingTags::AmazonEffect => write!(f, "amazon:effect"),
+      PossibleClosingTags::AmazonAutoBreaths => write!(f, "amazon:auto-breaths"),
+    }
+  }
+}
+
 impl FromStr for PossibleClosingTags {
     type Err = ();
 
@@ -415,6 +803,9 @@ impl FromStr for PossibleClosingTags {
         "say-as" => Ok(PossibleClosingTags::SayAs),
         "sub" => Ok(PossibleClosingTags::Sub),
         "w" => Ok(PossibleClosingTags::Word),
+        "emphasis" => Ok(PossibleClosingTags::Emphasis),
+        "audio" => Ok(PossibleClosingTags::Audio),
+        "voice" => Ok(PossibleClosingTags::Voice),
         "amazon:effect" => Ok(PossibleClosingTags::AmazonEffect),
         "amazon:auto-breaths" => Ok(PossibleClosingTags::AmazonAutoBreaths),
         _ => Err(()),
@@ -422,6 +813,8 @@ impl FromStr for PossibleClosingTags {
     }
 }
 
+/// Every tag that can be opened inside of the `${...}` markup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PossibleOpenTags {
   Break,
   LangTag,
@@ -433,11 +826,64 @@ pub enum PossibleOpenTags {
   SayAs,
   Sub,
   Word,
+  Emphasis,
+  Audio,
+  Voice,
   AmazonEffect,
   AmazonAutoBreaths,
   AmazonBreath,
 }
 
+impl PossibleOpenTags {
+
+  /// Every possible value, in the order they're declared.
+  pub fn variants() -> impl Iterator<Item = PossibleOpenTags> {
+    const VARIANTS: &[PossibleOpenTags] = &[
+      PossibleOpenTags::Break,
+      PossibleOpenTags::LangTag,
+      PossibleOpenTags::Mark,
+      PossibleOpenTags::Paragraph,
+      PossibleOpenTags::Phoneme,
+      PossibleOpenTags::Prosody,
+      PossibleOpenTags::Sentence,
+      PossibleOpenTags::SayAs,
+      PossibleOpenTags::Sub,
+      PossibleOpenTags::Word,
+      PossibleOpenTags::Emphasis,
+      PossibleOpenTags::Audio,
+      PossibleOpenTags::Voice,
+      PossibleOpenTags::AmazonEffect,
+      PossibleOpenTags::AmazonAutoBreaths,
+      PossibleOpenTags::AmazonBreath,
+    ];
+    VARIANTS.iter().copied()
+  }
+
+}
+
+impl fmt::Display for PossibleOpenTags {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      PossibleOpenTags::Break => write!(f, "break"),
+      PossibleOpenTags::LangTag => write!(f, "lang"),
+      PossibleOpenTags::Mark => write!(f, "mark"),
+      PossibleOpenTags::Paragraph => write!(f, "p"),
+      PossibleOpenTags::Phoneme => write!(f, "phoneme"),
+      PossibleOpenTags::Prosody => write!(f, "prosody"),
+      PossibleOpenTags::Sentence => write!(f, "s"),
+      PossibleOpenTags::SayAs => write!(f, "say-as"),
+      PossibleOpenTags::Sub => write!(f, "sub"),
+      PossibleOpenTags::Word => write!(f, "w"),
+      PossibleOpenTags::Emphasis => write!(f, "emphasis"),
+      PossibleOpenTags::Audio => write!(f, "audio"),
+      PossibleOpenTags::Voice => write!(f, "voice"),
+      PossibleOpenTags::AmazonEffect => write!(f, "amazon:effect"),
+      PossibleOpenTags::AmazonAutoBreaths => write!(f, "amazon:auto-breaths"),
+      PossibleOpenTags::AmazonBreath => write!(f, "amazon:breath"),
+    }
+  }
+}
+
 impl FromStr for PossibleOpenTags {
     type Err = ();
 
@@ -453,6 +899,9 @@ impl FromStr for PossibleOpenTags {
         "say-as" => Ok(PossibleOpenTags::SayAs),
         "sub" => Ok(PossibleOpenTags::Sub),
         "w" => Ok(PossibleOpenTags::Word),
+        "emphasis" => Ok(PossibleOpenTags::Emphasis),
+        "audio" => Ok(PossibleOpenTags::Audio),
+        "voice" => Ok(PossibleOpenTags::Voice),
         "amazon:effect" => Ok(PossibleOpenTags::AmazonEffect),
         "amazon:auto-breaths" => Ok(PossibleOpenTags::AmazonAutoBreaths),
         "amazon:breath" => Ok(PossibleOpenTags::AmazonBreath),
@@ -460,3 +909,45 @@ impl FromStr for PossibleOpenTags {
       }
     }
 }
+
+/// Implements `serde::Serialize`, and `serde::Deserialize` for types in this module
+/// using the same string form that ends up in the SSML (their `Display`, and `FromStr`).
+#[cfg(feature = "serde")]
+macro_rules! serde_as_ssml_string {
+  ($($name:ident),*) => {
+    $(
+      impl serde::Serialize for $name {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+          serializer.collect_str(self)
+        }
+      }
+
+      impl<'de> serde::Deserialize<'de> for $name {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<$name, D::Error> {
+          let as_string = String::deserialize(deserializer)?;
+          as_string.parse::<$name>().map_err(|_| serde::de::Error::custom(
+            format!("\"{}\" is not a valid {}", as_string, stringify!($name))
+          ))
+        }
+      }
+    )*
+  };
+}
+
+#[cfg(feature = "serde")]
+serde_as_ssml_string!(
+  BreakStrength,
+  BreakTime,
+  PhonemeAlphabet,
+  ProsodyRate,
+  WordRole,
+  EmphasisLevel,
+  PollyEngine,
+  AmazonEffect,
+  BreathVolumes,
+  BreathDuration,
+  AutoBreathFrequency,
+  PhonationVolume,
+  PossibleClosingTags,
+  PossibleOpenTags
+);
@@ -0,0 +1,320 @@
+//! Validates SSML that didn't come from us (e.g. from a third party) before it gets sent
+//! off to Polly. This checks the document against the SSML v1.1 rules Polly actually
+//! follows, along with Polly's own table of supported tags, and attributes which can be
+//! found: [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html).
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use std::collections::BTreeMap;
+
+use crate::diagnostics::Diagnostic;
+use crate::parser::Span;
+use crate::phonetics::validate_ph;
+use crate::ssml_constants::*;
+
+/// The rules for a single tag Polly supports.
+struct TagRule {
+  /// The name of the tag.
+  name: &'static str,
+  /// Attributes that must be present.
+  required: &'static [&'static str],
+  /// Attributes that may be present.
+  optional: &'static [&'static str],
+  /// If the tag may only contain text, and no other tags.
+  text_only: bool,
+  /// If the tag may not contain anything at all.
+  empty: bool,
+}
+
+/// Every tag Polly supports.
+const POLLY_TAGS: &[TagRule] = &[
+  TagRule { name: "speak", required: &[], optional: &["xml:lang", "onlangfailure", "version", "xmlns"], text_only: false, empty: false },
+  TagRule { name: "break", required: &[], optional: &["strength", "time"], text_only: false, empty: true },
+  TagRule { name: "lang", required: &["xml:lang"], optional: &["onlangfailure"], text_only: false, empty: false },
+  TagRule { name: "mark", required: &["name"], optional: &[], text_only: false, empty: false },
+  TagRule { name: "p", required: &[], optional: &[], text_only: false, empty: false },
+  TagRule { name: "phoneme", required: &["alphabet", "ph"], optional: &[], text_only: true, empty: false },
+  TagRule { name: "prosody", required: &[], optional: &["volume", "rate", "pitch", "amazon:max-duration"], text_only: false, empty: false },
+  TagRule { name: "s", required: &[], optional: &[], text_only: false, empty: false },
+  TagRule { name: "say-as", required: &["interpret-as"], optional: &["format", "detail"], text_only: true, empty: false },
+  TagRule { name: "sub", required: &["alias"], optional: &[], text_only: true, empty: false },
+  TagRule { name: "w", required: &["role"], optional: &[], text_only: false, empty: false },
+  TagRule { name: "emphasis", required: &[], optional: &["level"], text_only: false, empty: false },
+  TagRule { name: "amazon:effect", required: &[], optional: &["name", "vocal-tract-length", "phonation"], text_only: false, empty: false },
+  TagRule { name: "amazon:auto-breaths", required: &[], optional: &["volume", "frequency", "duration"], text_only: false, empty: false },
+  TagRule { name: "amazon:breath", required: &[], optional: &["volume", "duration"], text_only: false, empty: true },
+  TagRule { name: "amazon:domain", required: &["name"], optional: &[], text_only: false, empty: false },
+];
+
+/// Every `interpret-as` value Polly supports for the `say-as` tag.
+const POLLY_INTERPRET_AS: &[&str] = &[
+  "characters", "spell-out", "cardinal", "number", "ordinal", "digits", "fraction", "unit",
+  "date", "time", "address", "expletive", "telephone",
+];
+
+/// Every `name` Polly supports for the `amazon:domain` tag.
+const POLLY_DOMAINS: &[&str] = &["news", "conversational", "long-form"];
+
+/// Validates an SSML document, handing back every problem found. An empty list means
+/// the document should be good to send to Polly.
+///
+/// The checks are:
+///
+///   * The document is well formed XML.
+///   * `<speak>` is the root tag, and there's only one of them.
+///   * Every tag is one Polly supports, and has it's required attributes.
+///   * Every attribute value is one Polly accepts, including every symbol in a `ph`.
+///   * Tags are nested properly (no `<p>` inside of `<s>`, `<phoneme>` only containing
+///     text, nothing inside of `<break>`, etc.)
+///
+/// Unknown attributes are reported as warnings, everything else is an error.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::validator::validate_ssml;
+/// assert!(validate_ssml(r#"<speak><s>Hey <break time="1s"/> there.</s></speak>"#).is_empty());
+///
+/// let diagnostics = validate_ssml(r#"<speak><s><p>nope</p></s></speak>"#);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].message, "<p> can't be inside of <s>.");
+/// ```
+pub fn validate_ssml(ssml: &str) -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+  let mut reader = Reader::from_str(ssml);
+  let mut buf = Vec::new();
+  let mut open: Vec<String> = Vec::new();
+  // The `xml:lang` in scope for each open tag.
+  let mut languages: Vec<Option<String>> = Vec::new();
+  let mut seen_root = false;
+
+  loop {
+    let start = reader.buffer_position();
+    let event = match reader.read_event(&mut buf) {
+      Ok(event) => event,
+      Err(error) => {
+        let position = reader.buffer_position();
+        diagnostics.push(Diagnostic::error(format!("Invalid XML: {}", error),
+          Some(Span { start, end: position })));
+        break;
+      },
+    };
+    let span = Some(Span { start, end: reader.buffer_position() });
+
+    match event {
+      Event::Start(ref elem) | Event::Empty(ref elem) => {
+        let name = reader.decode(elem.name()).into_owned();
+        let is_empty = matches!(event, Event::Empty(_));
+
+        if open.is_empty() {
+          if seen_root {
+            diagnostics.push(Diagnostic::error("There can only be one root tag.".to_owned(), span));
+          } else if name != "speak" {
+            diagnostics.push(Diagnostic::error(format!("The root tag must be <speak>, not <{}>.", name), span));
+          }
+          seen_root = true;
+        } else if name == "speak" {
+          diagnostics.push(Diagnostic::error("<speak> can only be the root tag.".to_owned(), span));
+        }
+
+        if let Some(parent) = open.last() {
+          check_nesting(parent, &open, &name, span, &mut diagnostics);
+        }
+        let language = elem.attributes()
+          .flatten()
+          .find(|attribute| attribute.key == b"xml:lang")
+          .and_then(|attribute| attribute.unescape_and_decode_value(&reader).ok())
+          .or_else(|| languages.last().cloned().flatten());
+        check_tag(&reader, elem, &name, language.as_deref(), span, &mut diagnostics);
+
+        if !is_empty {
+          open.push(name);
+          languages.push(language);
+        }
+      },
+      Event::End(_) => {
+        open.pop();
+        languages.pop();
+      },
+      Event::Text(ref text) | Event::CData(ref text) => {
+        let is_blank = text.escaped().iter().all(|byte| (*byte as char).is_whitespace());
+        if is_blank {
+          buf.clear();
+          continue;
+        }
+        match open.last() {
+          Some(parent) => {
+            if let Some(rule) = find_rule(parent) {
+              if rule.empty {
+                diagnostics.push(Diagnostic::error(format!("<{}> can't contain anything.", parent), span));
+              }
+            }
+          },
+          None => {
+            diagnostics.push(Diagnostic::error("Text must be inside of the <speak> tag.".to_owned(), span));
+          },
+        };
+      },
+      Event::Eof => break,
+      _ => {},
+    };
+    buf.clear();
+  }
+
+  if !seen_root && diagnostics.is_empty() {
+    diagnostics.push(Diagnostic::error("The document is empty, it needs a <speak> tag.".to_owned(), None));
+  }
+
+  diagnostics
+}
+
+fn find_rule(name: &str) -> Option<&'static TagRule> {
+  POLLY_TAGS.iter().find(|rule| rule.name == name)
+}
+
+/// Checks that `name` is allowed to be inside of everything that's currently open.
+fn check_nesting(parent: &str, open: &[String], name: &str, span: Option<Span>,
+  diagnostics: &mut Vec<Diagnostic>) {
+  if let Some(rule) = find_rule(parent) {
+    if rule.empty {
+      diagnostics.push(Diagnostic::error(format!("<{}> can't contain anything.", parent), span));
+    } else if rule.text_only {
+      diagnostics.push(Diagnostic::error(format!("<{}> can only contain text, not <{}>.", parent, name), span));
+    }
+  }
+
+  let is_open = |tag: &str| open.iter().any(|open_tag| open_tag == tag);
+  match name {
+    "p" if is_open("s") => {
+      diagnostics.push(Diagnostic::error("<p> can't be inside of <s>.".to_owned(), span));
+    },
+    "p" if is_open("p") => {
+      diagnostics.push(Diagnostic::error("<p> can't be inside of another <p>.".to_owned(), span));
+    },
+    "s" if is_open("s") => {
+      diagnostics.push(Diagnostic::error("<s> can't be inside of another <s>.".to_owned(), span));
+    },
+    _ => {},
+  };
+}
+
+/// Checks that a tag is one Polly supports, and that it's attributes are valid. `language`
+/// is the `xml:lang` in scope, which decides the phonemes Polly supports.
+fn check_tag<B: std::io::BufRead>(reader: &Reader<B>, elem: &BytesStart, name: &str,
+  language: Option<&str>, span: Option<Span>, diagnostics: &mut Vec<Diagnostic>) {
+  let rule = match find_rule(name) {
+    Some(rule) => rule,
+    None => {
+      diagnostics.push(Diagnostic::error(format!("<{}> is not supported by Polly.", name), span));
+      return;
+    },
+  };
+
+  let mut attributes = BTreeMap::new();
+  for attribute in elem.attributes() {
+    match attribute {
+      Ok(attribute) => {
+        let key = reader.decode(attribute.key).into_owned();
+        let value = attribute.unescape_and_decode_value(reader).unwrap_or_default();
+        attributes.insert(key, value);
+      },
+      Err(error) => {
+        diagnostics.push(Diagnostic::error(format!("Invalid attribute on <{}>: {}", name, error), span));
+      },
+    };
+  }
+
+  for required in rule.required {
+    if !attributes.contains_key(*required) {
+      diagnostics.push(Diagnostic::error(
+        format!("<{}> is missing the required attribute \"{}\".", name, required), span));
+    }
+  }
+
+  for (key, value) in &attributes {
+    let is_known = rule.required.contains(&key.as_str()) || rule.optional.contains(&key.as_str());
+    let is_namespace = name == "speak" && (key.starts_with("xmlns:") || key.starts_with("xsi:"));
+    if !is_known && !is_namespace {
+      diagnostics.push(Diagnostic::warning(
+        format!("<{}> has an attribute Polly doesn't know about: \"{}\".", name, key), span));
+    } else if !is_valid_value(name, key, value) {
+      diagnostics.push(Diagnostic::error(
+        format!("\"{}\" is not a valid value for \"{}\" on <{}>.", value, key, name), span));
+    }
+  }
+
+  match name {
+    "phoneme" => {
+      let alphabet = attributes.get("alphabet").and_then(|alphabet| alphabet.parse::<PhonemeAlphabet>().ok());
+      if let (Some(alphabet), Some(ph)) = (alphabet, attributes.get("ph")) {
+        for diagnostic in validate_ph(ph, alphabet, language) {
+          diagnostics.push(Diagnostic::error(format!("Invalid \"ph\" on <phoneme>: {}", diagnostic.message), span));
+        }
+      }
+    },
+    "prosody" if attributes.is_empty() => {
+      diagnostics.push(Diagnostic::error("<prosody> needs at least one attribute.".to_owned(), span));
+    },
+    "amazon:effect" => {
+      let count = ["name", "vocal-tract-length", "phonation"].iter()
+        .filter(|key| attributes.contains_key(**key))
+        .count();
+      if count != 1 {
+        diagnostics.push(Diagnostic::error(
+          "<amazon:effect> needs exactly one of \"name\", \"vocal-tract-length\", or \"phonation\".".to_owned(),
+          span));
+      }
+    },
+    _ => {},
+  };
+}
+
+/// If `value` is something Polly accepts for the attribute `key` on the tag `name`.
+fn is_valid_value(name: &str, key: &str, value: &str) -> bool {
+  match (name, key) {
+    ("break", "strength") => value.parse::<BreakStrength>().is_ok(),
+    ("break", "time") => value.parse::<BreakTime>().is_ok_and(|time| time <= BreakTime::POLLY_MAX),
+    ("lang", "xml:lang") | ("speak", "xml:lang") => !value.is_empty(),
+    ("phoneme", "alphabet") => value.parse::<PhonemeAlphabet>().is_ok(),
+    ("prosody", "rate") => value.parse::<ProsodyRate>().is_ok() || is_number_with_suffix(value, "%", false),
+    ("prosody", "volume") => {
+      ["default", "silent", "x-soft", "soft", "medium", "loud", "x-loud"].contains(&value) ||
+        is_number_with_suffix(value, "dB", true)
+    },
+    ("prosody", "pitch") => {
+      ["default", "x-low", "low", "medium", "high", "x-high"].contains(&value) ||
+        is_number_with_suffix(value, "%", true)
+    },
+    ("prosody", "amazon:max-duration") => value.parse::<BreakTime>().is_ok(),
+    ("say-as", "interpret-as") => POLLY_INTERPRET_AS.contains(&value),
+    ("w", "role") => value.parse::<WordRole>().is_ok(),
+    ("emphasis", "level") => value.parse::<EmphasisLevel>().is_ok(),
+    ("amazon:effect", "name") => value.parse::<AmazonEffect>().is_ok(),
+    ("amazon:effect", "vocal-tract-length") => is_number_with_suffix(value, "%", false),
+    ("amazon:effect", "phonation") => value.parse::<PhonationVolume>().is_ok(),
+    ("amazon:auto-breaths", "volume") | ("amazon:breath", "volume") => {
+      value.parse::<BreathVolumes>().is_ok()
+    },
+    ("amazon:auto-breaths", "frequency") => value.parse::<AutoBreathFrequency>().is_ok(),
+    ("amazon:auto-breaths", "duration") | ("amazon:breath", "duration") => {
+      value.parse::<BreathDuration>().is_ok()
+    },
+    ("amazon:domain", "name") => POLLY_DOMAINS.contains(&value),
+    _ => true,
+  }
+}
+
+/// If `value` is a (possibly decimal) number followed by `suffix`, e.g. `+6dB`. When
+/// `needs_sign` is set the number must start with a `+` or `-`.
+fn is_number_with_suffix(value: &str, suffix: &str, needs_sign: bool) -> bool {
+  if !value.ends_with(suffix) {
+    return false;
+  }
+  let number = &value[..value.len() - suffix.len()];
+  let unsigned = number.trim_start_matches(['+', '-']);
+  if needs_sign && unsigned.len() == number.len() {
+    return false;
+  }
+  number.len() - unsigned.len() <= 1 && !unsigned.is_empty() && unsigned.parse::<f64>().is_ok()
+}
@@ -0,0 +1,180 @@
+//! Switching voices with `${voice|name=...}...${/voice}`. Most engines write this as a
+//! `<voice>` tag, but Polly synthesizes a whole request with a single voice. So for Polly
+//! `split_by_voice` splits the document up into one SSML document per run of text in the
+//! same voice, which can each be synthesized, and the audio concatenated.
+//!
+//! Each document is handed back as a `ParseOutput` rather than just it's SSML, so the
+//! warnings for it's part of the text stay with it.
+
+use failure::Error;
+
+use std::fmt;
+
+use crate::dialect::PollyDialect;
+use crate::document::is_empty_tag;
+use crate::diagnostics::Diagnostic;
+use crate::parser::{prepare_tokens, write_tokens, OneItem, ParseOptions, ParseOutput, Span};
+use crate::ssml_constants::PollyEngine;
+
+/// The name of a voice, e.g. `Joanna`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct VoiceId(String);
+
+impl VoiceId {
+
+  /// Creates a voice id from it's name.
+  pub fn new(name: &str) -> VoiceId {
+    VoiceId(name.to_owned())
+  }
+
+  /// The name of the voice.
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+
+}
+
+impl fmt::Display for VoiceId {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl<'a> From<&'a str> for VoiceId {
+  fn from(name: &'a str) -> VoiceId {
+    VoiceId::new(name)
+  }
+}
+
+/// Splits `data` up by voice, writing Polly's SSML for each run of text in the same voice.
+/// Text outside of any `${voice}` tag is said by `default_voice`, and a `${voice}` tag
+/// without a `name` keeps whatever voice was already being used. Tags that are open where
+/// the voice changes are closed, and opened again in the next document. That includes `p`,
+/// and `s`, so a voice change in the middle of a paragraph, or sentence splits it in two,
+/// each read as a paragraph (or sentence) of it's own. A run with nothing to say, and no
+/// pause (e.g. `${voice|name=Ivy}${mark|name=here}${/mark}${/voice}`) is folded into the
+/// run before it, and runs in the same voice that end up next to each other are joined.
+///
+/// Each document comes with the warnings for it's part of `data`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::ParseOptions;
+/// use text_to_polly_ssml::ssml_constants::PollyEngine;
+/// use text_to_polly_ssml::voice::{split_by_voice, VoiceId};
+/// let segments = split_by_voice(
+///   "${prosody|rate=slow}Hello ${voice|name=Matthew}there${/voice}${/prosody}",
+///   &ParseOptions::default(),
+///   &VoiceId::new("Joanna"),
+///   PollyEngine::Standard,
+/// ).unwrap();
+/// assert_eq!(segments.len(), 2);
+/// assert_eq!(segments[1].0, VoiceId::new("Matthew"));
+/// assert!(segments[1].1.ssml.ends_with(r#"><prosody rate="slow">there</prosody></speak>"#));
+/// assert!(segments[1].1.diagnostics.is_empty());
+/// ```
+pub fn split_by_voice(data: &str, options: &ParseOptions, default_voice: &VoiceId,
+  engine: PollyEngine) -> Result<Vec<(VoiceId, ParseOutput)>, Error> {
+  let mut diagnostics = Vec::new();
+  let parsed = prepare_tokens(data, options, &mut diagnostics)?;
+
+  let mut runs: Vec<Run> = Vec::new();
+  let mut pending: Option<Run> = None;
+  let mut voices: Vec<Option<VoiceId>> = Vec::new();
+  let voice_in_use = |voices: &[Option<VoiceId>]| voices.iter().rev().flatten().next()
+    .unwrap_or(default_voice).clone();
+  let mut current = Run { voice: default_voice.clone(), start: 0, items: Vec::new() };
+
+  for (span, item) in parsed {
+    if let Some(start_tag) = item.start_tag.as_ref().filter(|start_tag| start_tag.tag_key.eq_ignore_ascii_case("voice")) {
+      voices.push(start_tag.params.get("name").map(|name| VoiceId::new(name)));
+    } else if item.end_tag.as_ref().is_some_and(|end_tag| end_tag.tag_key.eq_ignore_ascii_case("voice")) {
+      voices.pop();
+    } else {
+      current.items.push((span, item));
+      continue;
+    }
+
+    let voice = voice_in_use(&voices);
+    if voice != current.voice {
+      let next = Run { voice, start: span.start, items: Vec::new() };
+      keep_run(std::mem::replace(&mut current, next), &mut runs, &mut pending);
+    }
+  }
+  keep_run(current, &mut runs, &mut pending);
+
+  let mut open: Vec<(Span, OneItem)> = Vec::new();
+  let mut segments: Vec<(VoiceId, ParseOutput)> = Vec::with_capacity(runs.len());
+  for (index, run) in runs.iter().enumerate() {
+    let mut items = open.clone();
+    for (span, item) in &run.items {
+      if let Some(ref start_tag) = item.start_tag {
+        if !is_empty_tag(&start_tag.tag_key) {
+          open.push((*span, item.clone()));
+        }
+      }
+      if let Some(ref end_tag) = item.end_tag {
+        let matching = open.iter().rposition(|(_, open_item)| open_item.start_tag.as_ref()
+          .is_some_and(|start_tag| start_tag.tag_key.eq_ignore_ascii_case(&end_tag.tag_key)));
+        if let Some(position) = matching {
+          open.remove(position);
+        }
+      }
+      items.push((*span, item.clone()));
+    }
+    for (span, open_item) in open.iter().rev() {
+      if let Some(ref start_tag) = open_item.start_tag {
+        items.push((*span, OneItem::end(&start_tag.tag_key)));
+      }
+    }
+
+    // Everything found before writing goes with the run it's in, or the first run if it
+    // isn't in any part of the text.
+    let end = runs.get(index + 1).map_or(usize::MAX, |next| next.start);
+    let (mut run_diagnostics, rest): (Vec<Diagnostic>, Vec<Diagnostic>) = diagnostics.into_iter()
+      .partition(|diagnostic| diagnostic.span.map_or(index == 0, |span| span.start < end));
+    diagnostics = rest;
+    let ssml = write_tokens(&items, options, PollyDialect::new(engine), &mut run_diagnostics)?;
+    segments.push((run.voice.clone(), ParseOutput { ssml, diagnostics: run_diagnostics }));
+  }
+
+  Ok(segments)
+}
+
+/// A run of tokens said in the same voice, starting at `start` in the text.
+struct Run {
+  voice: VoiceId,
+  start: usize,
+  items: Vec<(Span, OneItem)>,
+}
+
+impl Run {
+
+  /// If this run says anything, or at least pauses.
+  fn is_heard(&self) -> bool {
+    self.items.iter().any(|(_, item)| match (&item.start_tag, &item.data) {
+      (Some(start_tag), _) => is_empty_tag(&start_tag.tag_key),
+      (_, Some(data)) => !data.trim().is_empty(),
+      _ => false,
+    })
+  }
+
+}
+
+/// Adds `run` onto the runs that get written. A run with nothing to be heard is folded into
+/// the run before it (or the one after it, if it's the first), and a run in the same voice
+/// as the run before it is joined onto it.
+fn keep_run(mut run: Run, runs: &mut Vec<Run>, pending: &mut Option<Run>) {
+  if let Some(mut before) = pending.take() {
+    before.items.append(&mut run.items);
+    run.items = before.items;
+    run.start = before.start;
+  }
+  match runs.last_mut() {
+    Some(last) if last.voice == run.voice || !run.is_heard() => last.items.append(&mut run.items),
+    None if !run.is_heard() => *pending = Some(run),
+    _ => runs.push(run),
+  };
+}
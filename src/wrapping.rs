@@ -0,0 +1,81 @@
+//! Shared by the passes that rewrite the text tokens (abbreviations, the lexicon, and
+//! normalization), which all leave text inside of certain tags alone, and mostly wrap
+//! whole words in tags.
+
+use std::collections::BTreeMap;
+
+use crate::parser::{OneItem, Span};
+
+/// Hands every text token that isn't inside of a tag `skip` returns true for to `rewrite`,
+/// which pushes whatever should replace it. Every other token is kept as is.
+pub(crate) fn rewrite_text<S, R>(items: Vec<(Span, OneItem)>, skip: S, mut rewrite: R) -> Vec<(Span, OneItem)>
+  where S: Fn(&str) -> bool, R: FnMut(&str, Span, &mut Vec<(Span, OneItem)>) {
+  let mut result = Vec::with_capacity(items.len());
+  let mut skipping: Vec<String> = Vec::new();
+
+  for (span, item) in items {
+    if let Some(ref start_tag) = item.start_tag {
+      if skip(&start_tag.tag_key) {
+        skipping.push(start_tag.tag_key.to_lowercase());
+      }
+    }
+    if let Some(ref end_tag) = item.end_tag {
+      let name = end_tag.tag_key.to_lowercase();
+      if let Some(position) = skipping.iter().rposition(|open| *open == name) {
+        skipping.truncate(position);
+      }
+    }
+
+    match item.data {
+      Some(ref text) if skipping.is_empty() => rewrite(text, span, &mut result),
+      _ => result.push((span, item)),
+    };
+  }
+
+  result
+}
+
+/// If `rest` has a word boundary `length` bytes in.
+pub(crate) fn ends_word(rest: &str, length: usize) -> bool {
+  rest[length..].chars().next().map_or(true, |character| !character.is_alphanumeric())
+}
+
+/// Pushes `text` (which starts at `offset`) as tokens, wrapping matches in a tag. At every
+/// word boundary `find` is handed the rest of the text, and hands back the length of the
+/// match, along with the tag to wrap it in, if anything matches there.
+pub(crate) fn wrap_words<F>(text: &str, offset: usize, mut find: F, result: &mut Vec<(Span, OneItem)>)
+  where F: FnMut(&str) -> Option<(usize, String, BTreeMap<String, String>)> {
+  let mut last_end = 0;
+  let mut position = 0;
+  let mut previous: Option<char> = None;
+
+  while position < text.len() {
+    let rest = &text[position..];
+    let at_boundary = previous.map_or(true, |character| !character.is_alphanumeric());
+    let found = if at_boundary { find(rest) } else { None };
+    match found {
+      Some((length, tag_key, params)) => {
+        if last_end < position {
+          result.push((Span { start: offset + last_end, end: offset + position }, OneItem::text(&text[last_end..position])));
+        }
+        let span = Span { start: offset + position, end: offset + position + length };
+        let matched = &rest[..length];
+        result.push((span, OneItem::start(&tag_key, params)));
+        result.push((span, OneItem::text(matched)));
+        result.push((span, OneItem::end(&tag_key)));
+        position += length;
+        last_end = position;
+        previous = matched.chars().last();
+      },
+      None => {
+        let character = rest.chars().next().unwrap();
+        position += character.len_utf8();
+        previous = Some(character);
+      },
+    };
+  }
+
+  if last_end < text.len() {
+    result.push((Span { start: offset + last_end, end: offset + text.len() }, OneItem::text(&text[last_end..])));
+  }
+}
@@ -5,7 +5,7 @@ use failure::{Error, err_msg};
 use quick_xml::Writer;
 use quick_xml::events::{Event, BytesDecl, BytesEnd, BytesStart, BytesText};
 
-use std::io::Cursor;
+use std::io::{Cursor, Write};
 
 use crate::ssml_constants::*;
 
@@ -14,9 +14,13 @@ use crate::ssml_constants::*;
 /// You should probably never use this directly, instead interacting with the parser,
 /// however if you'd like to build your own parser, and just reuse the XML Rendering
 /// then you'd want to use this.
-pub struct XmlWriter {
+///
+/// By default this writes into an in memory buffer, however it can write into anything
+/// that implements `std::io::Write` (a file, a socket, an HTTP Body, etc.) by using
+/// `XmlWriter::from_writer`.
+pub struct XmlWriter<W: Write = Cursor<Vec<u8>>> {
   /// The XML Writer instance. The thing that actually writes the XML.
-  pub writer: Writer<Cursor<Vec<u8>>>,
+  pub writer: Writer<W>,
 }
 
 impl XmlWriter {
@@ -43,13 +47,78 @@ impl XmlWriter {
   /// assert!(result.is_ok());
   /// ```
   pub fn new() -> Result<XmlWriter, Error> {
-    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    XmlWriter::from_writer(Cursor::new(Vec::new()))
+  }
+
+  /// Renders the XML document in it's current state. This expects the document
+  /// to be completely valid UTF-8, and will do no closing of tags for you.
+  ///
+  /// This copies the entire buffer every time it's called, if you're done writing
+  /// you probably want `into_string` instead.
+  pub fn render(&mut self) -> String {
+    String::from_utf8(self.writer.clone().into_inner().into_inner()).expect("SSML is not valid UTF-8!")
+  }
+
+  /// Consumes the writer, and turns the XML document into a String without copying the
+  /// underlying buffer. Just like `render` this does no closing of tags for you.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut xml_writer = XmlWriter::new().unwrap();
+  /// assert!(xml_writer.start_ssml_paragraph().is_ok());
+  /// assert!(xml_writer.end_ssml_paragraph().is_ok());
+  /// assert_eq!(xml_writer.into_string().unwrap(), r#"<?xml version="1.0"?><p></p>"#);
+  /// ```
+  pub fn into_string(self) -> Result<String, Error> {
+    Ok(String::from_utf8(self.into_inner().into_inner())?)
+  }
+
+}
+
+impl<W: Write> XmlWriter<W> {
+
+  /// Creates a new XML Writer that streams into any `std::io::Write`. Rather than building
+  /// up the document in memory, every tag is written straight through as soon as it is
+  /// written. Just like `XmlWriter::new` the XML header is written immediately.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut xml_writer = XmlWriter::from_writer(Vec::new()).unwrap();
+  /// assert!(xml_writer.start_ssml_speak(None, None).is_ok());
+  /// assert!(xml_writer.write_text("hey").is_ok());
+  /// assert!(xml_writer.end_ssml_speak().is_ok());
+  /// let written = xml_writer.into_inner();
+  /// assert!(String::from_utf8(written).unwrap().ends_with("hey</speak>"));
+  /// ```
+  pub fn from_writer(inner: W) -> Result<XmlWriter<W>, Error> {
+    let mut writer = Writer::new(inner);
     writer.write_event(Event::Decl(BytesDecl::new(b"1.0", None, None)))?;
     Ok(XmlWriter {
-      writer: writer,
+      writer,
     })
   }
 
+  /// Creates a new XML Writer that streams into any `std::io::Write`, without writing the
+  /// XML header. Some engines (such as Alexa) refuse SSML that starts with one.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut xml_writer = XmlWriter::from_writer_without_declaration(Vec::new());
+  /// assert!(xml_writer.write_text("hey").is_ok());
+  /// assert_eq!(String::from_utf8(xml_writer.into_inner()).unwrap(), "hey");
+  /// ```
+  pub fn from_writer_without_declaration(inner: W) -> XmlWriter<W> {
+    XmlWriter {
+      writer: Writer::new(inner),
+    }
+  }
+
   /// Starts an SSML <speak> tag. For AWS Polly this is the root tag, and should only have one
   /// decleration as mentioned in their docs (As of April 20th, 2017):
   ///
@@ -174,11 +243,11 @@ impl XmlWriter {
     -> Result<usize, Error> {
     let mut elem = BytesStart::owned(b"break".to_vec(), "break".len());
 
-    if strength.is_some() {
-      elem.push_attribute(("strength", &*format!("{}", strength.unwrap())));
+    if let Some(strength) = strength {
+      elem.push_attribute(("strength", &*format!("{}", strength)));
     }
-    if time.is_some() {
-      elem.push_attribute(("time", &*format!("{}", time.unwrap())));
+    if let Some(time) = time {
+      elem.push_attribute(("time", &*format!("{}", time)));
     }
 
     Ok(self.writer.write_event(Event::Empty(elem))?)
@@ -490,14 +559,14 @@ impl XmlWriter {
     if volume.is_none() && rate.is_none() && pitch.is_none() {
       return Err(err_msg("Prosody Tag was supplied no values."))
     }
-    if volume.is_some() {
-      elem.push_attribute(("volume", &*volume.unwrap()));
+    if let Some(volume) = volume {
+      elem.push_attribute(("volume", &*volume));
     }
-    if rate.is_some() {
-      elem.push_attribute(("rate", &*format!("{}", rate.unwrap())));
+    if let Some(rate) = rate {
+      elem.push_attribute(("rate", &*format!("{}", rate)));
     }
-    if pitch.is_some() {
-      elem.push_attribute(("pitch", &*pitch.unwrap()));
+    if let Some(pitch) = pitch {
+      elem.push_attribute(("pitch", &*pitch));
     }
     Ok(self.writer.write_event(Event::Start(elem))?)
   }
@@ -616,6 +685,37 @@ impl XmlWriter {
     Ok(self.writer.write_event(Event::Start(elem))?)
   }
 
+  /// Starts an SSML say-as Tag with a `format`. Since this was first written AWS polly
+  /// has added support for `format` when `interpret-as` is `date`, which says the order
+  /// the parts of the date are written in (e.g. `mdy`, `dmy`, or `ymd`).
+  ///
+  /// # Examples
+  ///
+  /// Rust Code:
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut new_xml_writer = XmlWriter::new();
+  /// assert!(new_xml_writer.is_ok());
+  /// let start_say_as_result = new_xml_writer.unwrap()
+  ///   .start_ssml_say_as_with_format("date".to_owned(), "mdy".to_owned());
+  /// assert!(start_say_as_result.is_ok());
+  /// ```
+  ///
+  /// Generated SSML:
+  ///
+  /// ```text
+  /// <?xml version="1.0"?>
+  /// <say-as interpret-as="date" format="mdy">
+  /// ```
+  pub fn start_ssml_say_as_with_format(&mut self, interpret_as: String, format: String)
+    -> Result<usize, Error> {
+    let mut elem = BytesStart::owned(b"say-as".to_vec(), "say-as".len());
+    elem.push_attribute(("interpret-as", &*interpret_as));
+    elem.push_attribute(("format", &*format));
+    Ok(self.writer.write_event(Event::Start(elem))?)
+  }
+
   /// Ends an SSML <say-as> tag.
   ///
   /// # Examples
@@ -750,6 +850,169 @@ impl XmlWriter {
     Ok(self.writer.write_event(Event::End(BytesEnd::borrowed(b"w")))?)
   }
 
+  /// Starts an SSML Emphasis tag. Emphasis is only supported by Polly's standard voices,
+  /// the W3C Spec for it can be found
+  /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_emphasis),
+  /// and what Polly does with it is documented:
+  /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#emphasis-tag).
+  ///
+  /// # Examples
+  ///
+  /// Rust Code:
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// use text_to_polly_ssml::ssml_constants::EmphasisLevel;
+  /// let mut new_xml_writer = XmlWriter::new();
+  /// assert!(new_xml_writer.is_ok());
+  /// let start_emphasis_result = new_xml_writer.unwrap().start_ssml_emphasis(Some(EmphasisLevel::Strong));
+  /// assert!(start_emphasis_result.is_ok());
+  /// ```
+  ///
+  /// Generated SSML:
+  ///
+  /// ```text
+  /// <?xml version="1.0"?>
+  /// <emphasis level="strong">
+  /// ```
+  pub fn start_ssml_emphasis(&mut self, level: Option<EmphasisLevel>) -> Result<usize, Error> {
+    let mut elem = BytesStart::owned(b"emphasis".to_vec(), "emphasis".len());
+    if let Some(level) = level {
+      elem.push_attribute(("level", &*format!("{}", level)));
+    }
+    Ok(self.writer.write_event(Event::Start(elem))?)
+  }
+
+  /// Ends an SSML <emphasis> tag.
+  ///
+  /// # Examples
+  ///
+  /// Rust Code:
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut new_xml_writer = XmlWriter::new();
+  /// assert!(new_xml_writer.is_ok());
+  /// let end_emphasis_result = new_xml_writer.unwrap().end_ssml_emphasis();
+  /// assert!(end_emphasis_result.is_ok());
+  /// ```
+  ///
+  /// Generated SSML:
+  ///
+  /// ```text
+  /// <?xml version="1.0"?>
+  /// </emphasis>
+  /// ```
+  pub fn end_ssml_emphasis(&mut self) -> Result<usize, Error> {
+    Ok(self.writer.write_event(Event::End(BytesEnd::borrowed(b"emphasis")))?)
+  }
+
+  /// Starts an SSML Audio tag, anything inside of it is said if the audio can't be played.
+  /// Polly can't play audio, but most other engines can. The W3C Spec for it can be found
+  /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_audio).
+  ///
+  /// # Examples
+  ///
+  /// Rust Code:
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut new_xml_writer = XmlWriter::new();
+  /// assert!(new_xml_writer.is_ok());
+  /// let start_audio_result = new_xml_writer.unwrap()
+  ///   .start_ssml_audio("https://example.com/bell.mp3".to_owned());
+  /// assert!(start_audio_result.is_ok());
+  /// ```
+  ///
+  /// Generated SSML:
+  ///
+  /// ```text
+  /// <?xml version="1.0"?>
+  /// <audio src="https://example.com/bell.mp3">
+  /// ```
+  pub fn start_ssml_audio(&mut self, src: String) -> Result<usize, Error> {
+    let mut elem = BytesStart::owned(b"audio".to_vec(), "audio".len());
+    elem.push_attribute(("src", &*src));
+    Ok(self.writer.write_event(Event::Start(elem))?)
+  }
+
+  /// Ends an SSML <audio> tag.
+  ///
+  /// # Examples
+  ///
+  /// Rust Code:
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut new_xml_writer = XmlWriter::new();
+  /// assert!(new_xml_writer.is_ok());
+  /// let end_audio_result = new_xml_writer.unwrap().end_ssml_audio();
+  /// assert!(end_audio_result.is_ok());
+  /// ```
+  ///
+  /// Generated SSML:
+  ///
+  /// ```text
+  /// <?xml version="1.0"?>
+  /// </audio>
+  /// ```
+  pub fn end_ssml_audio(&mut self) -> Result<usize, Error> {
+    Ok(self.writer.write_event(Event::End(BytesEnd::borrowed(b"audio")))?)
+  }
+
+  /// Starts an SSML Voice tag, which switches to the voice `name` for everything inside of
+  /// it. Polly picks the voice per request instead, but most other engines support it. The
+  /// W3C Spec for it can be found
+  /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_voice).
+  ///
+  /// # Examples
+  ///
+  /// Rust Code:
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut new_xml_writer = XmlWriter::new();
+  /// assert!(new_xml_writer.is_ok());
+  /// let start_voice_result = new_xml_writer.unwrap().start_ssml_voice("Joanna".to_owned());
+  /// assert!(start_voice_result.is_ok());
+  /// ```
+  ///
+  /// Generated SSML:
+  ///
+  /// ```text
+  /// <?xml version="1.0"?>
+  /// <voice name="Joanna">
+  /// ```
+  pub fn start_ssml_voice(&mut self, name: String) -> Result<usize, Error> {
+    let mut elem = BytesStart::owned(b"voice".to_vec(), "voice".len());
+    elem.push_attribute(("name", &*name));
+    Ok(self.writer.write_event(Event::Start(elem))?)
+  }
+
+  /// Ends an SSML <voice> tag.
+  ///
+  /// # Examples
+  ///
+  /// Rust Code:
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut new_xml_writer = XmlWriter::new();
+  /// assert!(new_xml_writer.is_ok());
+  /// let end_voice_result = new_xml_writer.unwrap().end_ssml_voice();
+  /// assert!(end_voice_result.is_ok());
+  /// ```
+  ///
+  /// Generated SSML:
+  ///
+  /// ```text
+  /// <?xml version="1.0"?>
+  /// </voice>
+  /// ```
+  pub fn end_ssml_voice(&mut self) -> Result<usize, Error> {
+    Ok(self.writer.write_event(Event::End(BytesEnd::borrowed(b"voice")))?)
+  }
+
   /// Starts an SSML amazon effect tag. These tags are unique to AWS Polly. As such
   /// the only place they are documented is inside the AWS Docs themsleves which are:
   /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html).
@@ -829,7 +1092,7 @@ impl XmlWriter {
   /// ```
   pub fn start_ssml_vocal_tract_length(&mut self, factor: String) -> Result<usize, Error> {
     let mut elem = BytesStart::owned(b"amazon:effect".to_vec(), "amazon:effect".len());
-    elem.push_attribute(("vocal-tract-length", &*format!("{}", factor)));
+    elem.push_attribute(("vocal-tract-length", &*factor));
     Ok(self.writer.write_event(Event::Start(elem))?)
   }
 
@@ -969,10 +1232,70 @@ impl XmlWriter {
     )?)
   }
 
-  /// Renders the XML document in it's current state. This expects the document
-  /// to be completely valid UTF-8, and will do no closing of tags for you.
-  pub fn render(&mut self) -> String {
-    String::from_utf8(self.writer.clone().into_inner().into_inner()).expect("SSML is not valid UTF-8!")
+  /// Starts any tag, with the attributes given in order. This is for the tags other engines
+  /// support that Polly doesn't, you'll want the dedicated `start_ssml_*` functions for
+  /// everything else.
+  ///
+  /// # Examples
+  ///
+  /// Rust Code:
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut xml_writer = XmlWriter::new().unwrap();
+  /// assert!(xml_writer.start_element("emphasis", &[("level", "strong")]).is_ok());
+  /// assert!(xml_writer.end_element("emphasis").is_ok());
+  /// ```
+  ///
+  /// Generated SSML:
+  ///
+  /// ```text
+  /// <?xml version="1.0"?>
+  /// <emphasis level="strong"></emphasis>
+  /// ```
+  pub fn start_element(&mut self, name: &str, attributes: &[(&str, &str)]) -> Result<usize, Error> {
+    let mut elem = BytesStart::owned(name.as_bytes().to_vec(), name.len());
+    for attribute in attributes {
+      elem.push_attribute(*attribute);
+    }
+    Ok(self.writer.write_event(Event::Start(elem))?)
+  }
+
+  /// Ends any tag started with `start_element`.
+  pub fn end_element(&mut self, name: &str) -> Result<usize, Error> {
+    Ok(self.writer.write_event(Event::End(BytesEnd::owned(name.as_bytes().to_vec())))?)
+  }
+
+  /// Writes any tag that can't contain anything, with the attributes given in order.
+  ///
+  /// # Examples
+  ///
+  /// Rust Code:
+  ///
+  /// ```rust
+  /// use text_to_polly_ssml::xml_writer::XmlWriter;
+  /// let mut xml_writer = XmlWriter::new().unwrap();
+  /// assert!(xml_writer.write_empty_element("mark", &[("name", "intro")]).is_ok());
+  /// assert!(xml_writer.into_string().unwrap().ends_with(r#"<mark name="intro"/>"#));
+  /// ```
+  ///
+  /// Generated SSML:
+  ///
+  /// ```text
+  /// <?xml version="1.0"?>
+  /// <mark name="intro"/>
+  /// ```
+  pub fn write_empty_element(&mut self, name: &str, attributes: &[(&str, &str)]) -> Result<usize, Error> {
+    let mut elem = BytesStart::owned(name.as_bytes().to_vec(), name.len());
+    for attribute in attributes {
+      elem.push_attribute(*attribute);
+    }
+    Ok(self.writer.write_event(Event::Empty(elem))?)
+  }
+
+  /// Consumes the writer, handing back whatever it was writing into.
+  pub fn into_inner(self) -> W {
+    self.writer.into_inner()
   }
 
 }
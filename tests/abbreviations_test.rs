@@ -0,0 +1,55 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::abbreviations::{Abbreviation, AbbreviationDictionary, Expansion};
+use text_to_polly_ssml::parser::{parse_with_options, ParseOptions};
+
+#[test]
+fn test_parsing_with_abbreviations() {
+  let mut dictionary = AbbreviationDictionary::built_in("en-US");
+  dictionary.insert(Abbreviation::alias("Ops", "operations"));
+  let options = ParseOptions { abbreviations: Some(dictionary), ..ParseOptions::default() };
+  let output = parse_with_options(
+    "Approx. 3 APIs, e.g. the API and SLA (see ${say-as|interpret-as=spell-out}SLA${/say-as}). Ops, ops, sla, i.e.x",
+    &options,
+  ).unwrap();
+  assert!(output.ssml.ends_with(concat!(
+    r#">"#,
+    r#"<sub alias="approximately">Approx.</sub> 3 APIs, <sub alias="for example">e.g.</sub> the "#,
+    r#"<say-as interpret-as="characters">API</say-as> and <say-as interpret-as="characters">SLA</say-as> "#,
+    r#"(see <say-as interpret-as="spell-out">SLA</say-as>). <sub alias="operations">Ops</sub>, ops, sla, i.e.x</speak>"#,
+  )), "{}", output.ssml);
+}
+
+#[test]
+fn test_layering_dictionaries() {
+  let mut dictionary = AbbreviationDictionary::built_in("en");
+  let mut custom = AbbreviationDictionary::default();
+  custom.insert(Abbreviation { case_sensitive: true, ..Abbreviation::alias("etc.", "and so on") });
+  custom.insert(Abbreviation::characters("SRE"));
+  let before = dictionary.abbreviations.len();
+  dictionary.extend(custom);
+
+  assert_eq!(dictionary.abbreviations.len(), before + 1);
+  assert_eq!(dictionary.lookup("etc.").map(|found| &found.expansion), Some(&Expansion::Alias("and so on".to_owned())));
+  assert_eq!(dictionary.lookup("Etc."), None);
+  assert_eq!(dictionary.lookup("SRE").map(|found| &found.expansion), Some(&Expansion::Characters));
+  assert_eq!(AbbreviationDictionary::built_in("de_DE").lookup("z.B.").map(|found| &found.expansion),
+    Some(&Expansion::Alias("zum Beispiel".to_owned())));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_loading_toml() {
+  let dictionary = AbbreviationDictionary::from_toml(r#"
+    case_sensitive = true
+    characters = ["sla"]
+
+    [aliases]
+    "approx." = "approximately"
+  "#).unwrap();
+  assert_eq!(dictionary.abbreviations.len(), 2);
+  assert!(dictionary.lookup("sla").is_some());
+  assert!(dictionary.lookup("SLA").is_none());
+  assert!(dictionary.lookup("Approx.").is_none());
+  assert!(AbbreviationDictionary::from_toml("characters = \"SLA\"").is_err());
+}
@@ -0,0 +1,148 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::audio::{read_audio_info, AudioFormat, AudioInfo, AudioOptions};
+use text_to_polly_ssml::dialect::{AlexaDialect, AzureDialect, GoogleDialect, W3cDialect};
+use text_to_polly_ssml::parser::{parse_with_dialect, parse_with_options, ParseOptions, StreamingParser};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A directory holding a WAV file at 16000Hz, and an MP3 file at 24000Hz (after an ID3 tag).
+fn sounds_directory(test: &str) -> PathBuf {
+  let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("sounds-{}-{}", test, std::process::id()));
+  fs::create_dir_all(&directory).unwrap();
+
+  let mut wav = b"RIFF\x24\x00\x00\x00WAVE".to_vec();
+  wav.extend_from_slice(b"LIST\x03\x00\x00\x00abc\x00");
+  wav.extend_from_slice(b"fmt \x10\x00\x00\x00\x01\x00\x01\x00\x80\x3e\x00\x00\x00\x7d\x00\x00\x02\x00\x10\x00");
+  fs::write(directory.join("beep.wav"), wav).unwrap();
+
+  let mut mp3 = b"ID3\x03\x00\x00\x00\x00\x00\x04junk".to_vec();
+  mp3.extend_from_slice(&[0xff, 0xf3, 0x64, 0xc4, 0x00, 0x00]);
+  fs::write(directory.join("horn.mp3"), mp3).unwrap();
+
+  fs::write(directory.join("notes.txt"), "not audio").unwrap();
+  fs::copy(directory.join("horn.mp3"), directory.join("fog horn.mp3")).unwrap();
+  directory
+}
+
+#[test]
+fn test_audio_fallback() {
+  let text = "${audio|src=https://example.com/a.mp3}bell${/audio} and ${audio|src=https://example.com/b.mp3} done";
+
+  let output = parse_with_dialect(text, &ParseOptions::default(), GoogleDialect::default()).unwrap();
+  assert!(output.ssml.ends_with(concat!(
+    r#"<speak><audio src="https://example.com/a.mp3">bell</audio> and "#,
+    r#"<audio src="https://example.com/b.mp3"></audio> done</speak>"#,
+  )));
+
+  let output = parse_with_dialect(text, &ParseOptions::default(), AlexaDialect::default()).unwrap();
+  assert_eq!(output.ssml, concat!(
+    r#"<speak><audio src="https://example.com/a.mp3"/> and "#,
+    r#"<audio src="https://example.com/b.mp3"/> done</speak>"#,
+  ));
+
+  let output = parse_with_options(text, &ParseOptions::default()).unwrap();
+  assert!(output.ssml.ends_with(">bell and  done</speak>"));
+  assert_eq!(output.diagnostics.len(), 2);
+  assert_eq!(output.diagnostics[0].message, "Polly doesn't support <audio>, it was left out.");
+
+  let mut parser = StreamingParser::with_dialect(Vec::new(), W3cDialect::default()).unwrap();
+  assert_eq!(parser.feed("hi ${audio|src=a.wav}").unwrap().len(), 2);
+  assert_eq!(parser.feed("${desc}a bell${/desc}ring${/au").unwrap().len(), 4);
+  assert_eq!(parser.feed("dio} ${audio|src=b.wav}${break|time=1s}").unwrap().len(), 5);
+  assert!(parser.diagnostics().is_empty());
+  assert_eq!(parser.feed("${audio|src=ftp://c.wav}drum${/audio} ${audio|src=d.wav}gong${break|time=1s}").unwrap().len(), 6);
+  assert_eq!(parser.diagnostics().len(), 2);
+  assert_eq!(parser.diagnostics()[1].message,
+    "<audio> wasn't closed with ${/audio}, so the text after it was used as it's fallback.");
+  let ssml = String::from_utf8(parser.finish().unwrap()).unwrap();
+  assert!(ssml.ends_with(concat!(
+    r#">hi <audio src="a.wav"><desc>a bell</desc>ring</audio> "#,
+    r#"<audio src="b.wav"></audio><break time="1s"/>drum <audio src="d.wav">gong</audio><break time="1s"/></speak>"#,
+  )));
+}
+
+#[test]
+fn test_audio_descriptions() {
+  let text = "${audio|src=https://example.com/dog.mp3}${desc}A dog barks${/desc}Dog barking${/audio} then ${desc}x${/desc}";
+  let options = ParseOptions::default();
+
+  let output = parse_with_options(text, &options).unwrap();
+  assert!(output.ssml.ends_with(">Dog barking then </speak>"));
+  assert_eq!(output.diagnostics.len(), 3);
+  let output = parse_with_dialect(text, &options, GoogleDialect::default()).unwrap();
+  assert!(output.ssml.ends_with(
+    r#"<speak><audio src="https://example.com/dog.mp3"><desc>A dog barks</desc>Dog barking</audio> then </speak>"#));
+  assert_eq!(output.diagnostics.len(), 1);
+  let output = parse_with_dialect(text, &options, AzureDialect::new("en-US-JennyNeural")).unwrap();
+  assert!(output.ssml.ends_with(
+    r#"<voice name="en-US-JennyNeural"><audio src="https://example.com/dog.mp3">Dog barking</audio> then </voice></speak>"#));
+  assert_eq!(output.diagnostics.len(), 2);
+  let output = parse_with_dialect(text, &options, AlexaDialect::default()).unwrap();
+  assert_eq!(output.ssml, r#"<speak><audio src="https://example.com/dog.mp3"/> then </speak>"#);
+  let output = parse_with_dialect(text, &options, W3cDialect::default()).unwrap();
+  assert!(output.ssml.ends_with(
+    r#"><audio src="https://example.com/dog.mp3"><desc>A dog barks</desc>Dog barking</audio> then </speak>"#));
+}
+
+#[test]
+fn test_audio_sources() {
+  let output = parse_with_dialect("${audio|src=ftp://example.com/a.mp3}bell${/audio}",
+    &ParseOptions::default(), GoogleDialect::default()).unwrap();
+  assert!(output.ssml.ends_with("<speak>bell</speak>"));
+  assert_eq!(output.diagnostics[0].message,
+    "\"ftp://example.com/a.mp3\" isn't an http, https, or file URL, or a relative path. The audio was left out.");
+  assert_eq!(output.diagnostics[0].span.map(|span| (span.start, span.end)), Some((0, 36)));
+
+  let options = ParseOptions { strict: true, ..ParseOptions::default() };
+  assert!(parse_with_dialect("${audio|src=s3://bucket/a.mp3}", &options, GoogleDialect::default()).is_err());
+  assert!(parse_with_dialect("${audio|src=sounds/a.mp3}", &options, GoogleDialect::default()).is_ok());
+
+  let directory = sounds_directory("sources");
+  let options = ParseOptions { audio: Some(AudioOptions::new(&directory)), ..ParseOptions::default() };
+  let output = parse_with_dialect(
+    concat!(
+      "${audio|src=beep.wav}${/audio}${audio|src=horn.mp3}${/audio}${audio|src=https://example.com/a.mp3}${/audio}",
+      "${audio|src=missing.mp3}missing${/audio}${audio|src=notes.txt}notes${/audio}",
+    ),
+    &options,
+    GoogleDialect::default(),
+  ).unwrap();
+  assert!(output.ssml.ends_with(concat!(
+    r#"<speak><audio src="beep.wav"></audio><audio src="horn.mp3"></audio>"#,
+    r#"<audio src="https://example.com/a.mp3"></audio>missingnotes</speak>"#,
+  )));
+  assert_eq!(output.diagnostics.len(), 2);
+
+  let options = ParseOptions { audio: Some(AudioOptions::alexa(&directory)), ..ParseOptions::default() };
+  let src = format!("file://{}", directory.join("beep.wav").display());
+  let output = parse_with_dialect(&format!("${{audio|src={}}}", src), &options, AlexaDialect::default()).unwrap();
+  assert_eq!(output.ssml, "<speak></speak>");
+  assert_eq!(output.diagnostics[0].message,
+    format!("\"{}\" is a WAV file, which isn't supported. The audio was left out.", directory.join("beep.wav").display()));
+
+  let text = format!("${{audio|src=fog%20horn.mp3}}${{/audio}}${{audio|src=file://localhost{}}}${{/audio}}",
+    directory.join("fog%20horn.mp3").display());
+  let output = parse_with_dialect(&text, &options, AlexaDialect::default()).unwrap();
+  assert_eq!(output.ssml.matches("<audio").count(), 2);
+  assert!(output.diagnostics.is_empty());
+  let output = parse_with_dialect("${audio|src=file://server/sounds/missing.mp3}${/audio}", &options,
+    AlexaDialect::default()).unwrap();
+  assert!(output.diagnostics.is_empty());
+
+  fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_read_audio_info() {
+  let directory = sounds_directory("info");
+  assert_eq!(read_audio_info(directory.join("beep.wav")).unwrap(),
+    AudioInfo { format: AudioFormat::Wav, sample_rate: 16_000, channels: 1 });
+  assert_eq!(read_audio_info(directory.join("horn.mp3")).unwrap(),
+    AudioInfo { format: AudioFormat::Mp3, sample_rate: 24_000, channels: 1 });
+  assert!(read_audio_info(directory.join("notes.txt")).is_err());
+  assert!(read_audio_info(directory.join("missing.mp3")).is_err());
+
+  fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,33 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::builder::*;
+use text_to_polly_ssml::ssml_constants::*;
+
+#[test]
+fn test_builder_matches_parser() {
+  let built = Ssml::speak()
+    .paragraph(|p| p
+      .sentence(|s| s.text("Hello ").sub("mercury", |sub| sub.text("hg")))
+      .brk_strength(BreakStrength::Strong)
+      .prosody(|pr| pr.volume("+6dB").rate(ProsodyRate::XFast).pitch("+4%")
+        .amazon_effect(AmazonEffect::Whispered, |e| e.text("secret"))))
+    .breath(BreathVolumes::XLoud, BreathDuration::XLong)
+    .render();
+  let parsed = text_to_polly_ssml::parse_string(r#"${p}${s}Hello ${sub|alias=mercury}hg${/sub}${/s}${break|strength=strong}${prosody|volume=+6dB|rate=x-fast|pitch=+4%}${amazon:effect|name=whispered}secret${/amazon:effect}${/prosody}${/p}${amazon:breath|volume=x-loud|duration=x-long}"#.to_owned());
+  assert!(built.is_ok());
+  assert_eq!(built.unwrap(), parsed.unwrap());
+}
+
+#[test]
+fn test_builder_with_lang() {
+  let built = Ssml::speak_with_lang("fr-FR", Some("changevoice"))
+    .lang("en-US", None, |l| l.text("hey"))
+    .render();
+  assert_eq!(built.unwrap(), r#"<?xml version="1.0"?><speak xml:lang="fr-FR" onlangfailure="changevoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><lang xml:lang="en-US" onlangfailure="processorchoice">hey</lang></speak>"#);
+}
+
+#[test]
+fn test_builder_empty_prosody_fails() {
+  let built = Ssml::speak().prosody(|p| p.text("nothing set")).render();
+  assert!(built.is_err());
+}
@@ -0,0 +1,126 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::dialect::{AlexaDialect, AzureDialect, GoogleDialect, W3cDialect};
+use text_to_polly_ssml::parser::{parse_with_dialect, ParseOptions, StreamingParser};
+
+use std::time::Duration;
+
+#[test]
+fn test_google_dialect() {
+  let output = parse_with_dialect(concat!(
+    "${par}${media|begin=0.5s|fadeInDur=2s}${audio|src=https://example.com/a.mp3}bell${/audio}${/media}",
+    "${media}${seq}${say-as|interpret-as=number}12${/say-as} ${say-as|interpret-as=address}1 Main St${/say-as}",
+    "${/seq}${/media}${/par}${mark|name=here}${/mark} ${w|role=amazon:VB}read${/w} ${amazon:breath|volume=x-loud|duration=long}",
+    "${sub}W3C${/sub} ${emphasis|level=loud}!${/emphasis}${break|time=1s}",
+  ), &ParseOptions::default(), GoogleDialect::default()).unwrap();
+
+  assert_eq!(output.ssml, concat!(
+    r#"<?xml version="1.0"?><speak><par><media begin="0.5s" fadeInDur="2s"><audio src="https://example.com/a.mp3">bell</audio></media>"#,
+    r#"<media><seq><say-as interpret-as="cardinal">12</say-as> 1 Main St</seq></media></par><mark name="here"/> read "#,
+    r#"W3C <emphasis>!</emphasis><break time="1s"/></speak>"#,
+  ));
+  let messages = output.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect::<Vec<&str>>();
+  assert_eq!(messages, vec![
+    r#"Google doesn't support <say-as interpret-as="address">, it was left out."#,
+    "Google doesn't support <w>, it was left out.",
+    "Google doesn't support <amazon:breath>, it was left out.",
+    r#"<sub> is missing "alias", it was left out."#,
+    r#""loud" is not an emphasis level Google supports, the default was used."#,
+  ]);
+
+  let options = ParseOptions { strict: true, ..ParseOptions::default() };
+  assert!(parse_with_dialect("${w|role=amazon:VB}read${/w}", &options, GoogleDialect::default()).is_err());
+}
+
+#[test]
+fn test_azure_dialect() {
+  let output = parse_with_dialect(concat!(
+    "\n${voice|name=de-DE-KatjaNeural}${p}${voice|name=x}Hallo${/voice}${/p}${/voice}\n",
+    "${amazon:effect|name=whispered}psst${/amazon:effect} ${mark|name=a}",
+    "${prosody|rate=250%|volume=-6dB|pitch=+5%}fast${/prosody}${prosody|volume=+6}${prosody|rate=x-slow}slow${/prosody}${/prosody}",
+    "${amazon:effect|phonation=soft}${w|role=amazon:VB}read${/w}${/amazon:effect}",
+  ), &ParseOptions::default(), AzureDialect::new("en-GB-SoniaNeural")).unwrap();
+
+  assert_eq!(output.ssml, concat!(
+    r#"<?xml version="1.0"?><speak version="1.0" xmlns="http://www.w3.org/2001/10/synthesis" "#,
+    r#"xmlns:mstts="https://www.w3.org/2001/mstts" xml:lang="en-GB">"#, "\n",
+    r#"<voice name="de-DE-KatjaNeural"><p>Hallo</p></voice>"#, "\n",
+    r#"<voice name="en-GB-SoniaNeural"><mstts:express-as style="whispering">psst</mstts:express-as> <bookmark mark="a"/>"#,
+    r#"<prosody pitch="+5%" rate="+100%" volume="-50%">fast</prosody><prosody rate="x-slow">slow</prosody>read</voice></speak>"#,
+  ));
+  let messages = output.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect::<Vec<&str>>();
+  assert_eq!(messages, vec![
+    "Azure can't put a <voice> inside of another tag, it was left out.",
+    "A prosody rate of 250% is outside of what Azure supports, +100% was used instead.",
+    r#"Azure doesn't support a prosody volume of "+6", it was left out."#,
+    "Azure doesn't support <amazon:effect>, it was left out.",
+    "Azure doesn't support <w>, it was left out.",
+  ]);
+
+  let output = parse_with_dialect("${voice|name=de-DE-KatjaNeural}${p}x${/voice}y${/p}", &ParseOptions::default(),
+    AzureDialect::new("en-GB-SoniaNeural")).unwrap();
+  assert!(output.ssml.ends_with(
+    r#"><voice name="de-DE-KatjaNeural"><p>x</p></voice><voice name="en-GB-SoniaNeural"><p>y</p></voice></speak>"#));
+  assert!(output.diagnostics.is_empty());
+}
+
+#[test]
+fn test_alexa_dialect() {
+  let output = parse_with_dialect(concat!(
+    "${amazon:domain|name=music}${voice|name=Kendra}Up next${/voice}${/amazon:domain} ",
+    "${audio|src=a}${audio|src=long}${audio|src=b}${audio|src=c}${audio|src=d}${audio|src=e}${audio|src=f}",
+    "${amazon:auto-breaths|volume=x-loud|frequency=low|duration=long}${mark|name=x}${/mark}${/amazon:auto-breaths}",
+    "${amazon:effect|name=whispered}${amazon:emotion|name=excited}psst${/amazon:emotion}${/amazon:effect}",
+  ), &ParseOptions::default(), AlexaDialect::default()
+    .with_audio_duration("long", Duration::from_secs(200))
+    .with_audio_duration("c", Duration::from_secs(41))).unwrap();
+
+  assert_eq!(output.ssml, concat!(
+    r#"<speak><amazon:domain name="music"><voice name="Kendra">Up next</voice></amazon:domain> "#,
+    r#"<audio src="a"/><audio src="long"/><audio src="b"/><audio src="d"/><audio src="e"/>"#,
+    r#"<amazon:effect name="whispered">psst</amazon:effect></speak>"#,
+  ));
+  let messages = output.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect::<Vec<&str>>();
+  assert_eq!(messages, vec![
+    r#"Alexa only plays 240 seconds of audio in a response, "c" was left out."#,
+    r#"Alexa only plays 5 audio clips in a response, "f" was left out."#,
+    "Alexa doesn't support <amazon:auto-breaths>, it was left out.",
+    "Alexa doesn't support <mark>, it was left out.",
+    r#"<amazon:emotion> is missing "intensity", it was left out."#,
+  ]);
+}
+
+#[test]
+fn test_w3c_dialect() {
+  let output = parse_with_dialect(concat!(
+    "${meta|name=seeAlso|content=https://example.com}${metadata}about${/metadata}\n",
+    "${voice|gender=female}${audio|src=bell.wav}${desc|lang=en}a bell${/desc}ding${/audio}${/voice} ",
+    "${desc}x${/desc}${lexicon|uri=late.pls}${amazon:auto-breaths|volume=low|frequency=low|duration=short}",
+    "${w|role=amazon:VB}read${/w}${/amazon:auto-breaths} ${say-as|interpret-as=characters}W3C${/say-as}",
+  ), &ParseOptions::default(), W3cDialect::default()).unwrap();
+
+  assert_eq!(output.ssml, concat!(
+    r#"<?xml version="1.0"?><speak version="1.1" xmlns="http://www.w3.org/2001/10/synthesis" xml:lang="en-US">"#,
+    r#"<meta name="seeAlso" content="https://example.com"/><metadata>about</metadata>"#, "\n",
+    r#"<voice gender="female"><audio src="bell.wav"><desc xml:lang="en">a bell</desc>ding</audio></voice> "#,
+    r#"<w>read</w> <say-as interpret-as="characters">W3C</say-as></speak>"#,
+  ));
+  let messages = output.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect::<Vec<&str>>();
+  assert_eq!(messages, vec![
+    "<desc> can only be used inside of <audio>, it was left out.",
+    "<lexicon> has to come before anything else, it was left out.",
+    "SSML 1.1 doesn't support <amazon:auto-breaths>, it was left out.",
+    "SSML 1.1 doesn't define Polly's word roles, the role was left out.",
+  ]);
+}
+
+#[test]
+fn test_streaming_with_dialect() {
+  let mut parser = StreamingParser::with_dialect(Vec::new(), GoogleDialect::default()).unwrap();
+  parser.feed("hey ${amazon:effect|name=d").unwrap();
+  parser.feed("rc}there${/amazon:effect}").unwrap();
+  assert_eq!(parser.diagnostics().len(), 1);
+  assert_eq!(parser.diagnostics()[0].span.map(|span| (span.start, span.end)), Some((4, 29)));
+  let ssml = String::from_utf8(parser.finish().unwrap()).unwrap();
+  assert_eq!(ssml, r#"<?xml version="1.0"?><speak>hey there</speak>"#);
+}
@@ -0,0 +1,55 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::document::{Document, Node};
+use text_to_polly_ssml::parser::Span;
+
+const MARKUP: &str = r#"${p}Hello ${break|time=4s}${prosody|volume=+6dB|rate=x-fast}loud ${sub|alias=mercury}hg${/sub}${/prosody}${/p} unclosed ${s}text $\{p}"#;
+
+#[test]
+fn test_document_renders_like_the_parser() {
+  let document = Document::from_markup(MARKUP).unwrap();
+  assert_eq!(document.render().unwrap(), text_to_polly_ssml::parse_string(MARKUP.to_owned()).unwrap());
+}
+
+#[test]
+fn test_document_limits_breaks() {
+  let document = Document::from_markup("a ${break|time=20s} b").unwrap();
+  assert!(document.render().unwrap().ends_with(r#">a <break time="10s"/> b</speak>"#));
+}
+
+#[test]
+fn test_document_structure() {
+  let document = Document::from_markup(MARKUP).unwrap();
+  assert_eq!(document.nodes.len(), 3);
+  match document.nodes[0] {
+    Node::Element(ref paragraph) => {
+      assert!(paragraph.closed);
+      assert_eq!(paragraph.span, Some(Span { start: 0, end: 110 }));
+      assert_eq!(paragraph.children.len(), 3);
+      match paragraph.children[2] {
+        Node::Element(ref prosody) => {
+          assert_eq!(prosody.name, "prosody");
+          assert_eq!(prosody.attributes.get("rate"), Some(&"x-fast".to_owned()));
+        },
+        _ => panic!("Expected prosody!"),
+      };
+    },
+    _ => panic!("Expected a paragraph!"),
+  };
+  match document.nodes[2] {
+    Node::Element(ref sentence) => {
+      assert!(!sentence.closed);
+      assert_eq!(sentence.children, vec![Node::text("text ${p}").with_span(124, 134)]);
+    },
+    _ => panic!("Expected a sentence!"),
+  };
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_document_json_round_trip() {
+  let document = Document::from_markup(MARKUP).unwrap();
+  let json = document.to_json().unwrap();
+  assert_eq!(Document::from_json(&json).unwrap(), document);
+  assert_eq!(text_to_polly_ssml::document::render_json(&json).unwrap(), document.render().unwrap());
+}
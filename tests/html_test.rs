@@ -0,0 +1,68 @@
+#![cfg(feature = "html")]
+
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::dialect::PollyDialect;
+use text_to_polly_ssml::html::{html_to_ssml, ElementMapping, HtmlOptions};
+use text_to_polly_ssml::parser::ParseOptions;
+use text_to_polly_ssml::ssml_constants::{BreakStrength, PollyEngine};
+
+#[test]
+fn test_html_to_ssml() {
+  let html = r#"<!DOCTYPE html>
+<html>
+  <head><title>My Blog</title><style>p { color: red; }</style></head>
+  <body>
+    <nav><a href="/">Home</a></nav>
+    <article>
+      <h2>Release   notes</h2>
+      Intro, costs ${5}.
+      <p>Fixed <em>two</em> bugs.<br>Thanks!</p>
+      <ul>
+        <li>First</li>
+        <li>Second
+          <ol><li>Nested</li></ol>
+        </li>
+      </ul>
+      <blockquote><abbr>TTS</abbr> is <strong>fun <div>really</div></strong></blockquote>
+    </article>
+    <script>alert("hi");</script>
+  </body>
+</html>"#;
+  let output = html_to_ssml(html, &HtmlOptions::default(), &ParseOptions::default(), PollyDialect::default()).unwrap();
+
+  assert!(output.ssml.ends_with(concat!(
+    r#"><break strength="strong"/><p><prosody volume="loud" rate="slow">Release notes</prosody></p>"#,
+    r#"<p>Intro, costs ${5}.</p><p>Fixed <emphasis level="moderate">two</emphasis> bugs.<break strength="weak"/>Thanks!</p>"#,
+    r#"<s>First</s><break strength="medium"/><s>Second</s><break strength="medium"/>"#,
+    r#"<s>Nested</s><break strength="medium"/>"#,
+    r#"<p>TTS is <emphasis level="strong">fun really</emphasis></p></speak>"#,
+  )));
+  assert!(output.diagnostics.is_empty());
+}
+
+#[test]
+fn test_html_mappings() {
+  let mut html_options = HtmlOptions::default();
+  html_options.insert("nav", ElementMapping::Sentence);
+  html_options.insert("br", ElementMapping::Break(BreakStrength::Strong));
+  html_options.insert("em", ElementMapping::Drop);
+
+  let html = "<nav>Menu</nav><p>Now <em>hidden</em>and<br/><strong>loud</strong></p>";
+  let output = html_to_ssml(html, &html_options, &ParseOptions::default(),
+    PollyDialect::new(PollyEngine::Neural)).unwrap();
+
+  assert!(output.ssml.ends_with(
+    r#"><s>Menu</s><p>Now and<break strength="strong"/><prosody volume="+6dB" rate="slow">loud</prosody></p></speak>"#));
+  assert_eq!(output.diagnostics.len(), 1);
+  assert_eq!(output.diagnostics[0].span.map(|span| &html[span.start..span.end]), Some("<strong>"));
+}
+
+#[test]
+fn test_html_leading_break() {
+  let output = html_to_ssml("<p><br>Hello</p><li><br>there</li>", &HtmlOptions::default(), &ParseOptions::default(),
+    PollyDialect::default()).unwrap();
+
+  assert!(output.ssml.ends_with(
+    r#"><p><break strength="weak"/>Hello</p><s><break strength="weak"/>there</s><break strength="medium"/></speak>"#));
+}
@@ -0,0 +1,82 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::lexicon::{Lexicon, Pronunciation};
+use text_to_polly_ssml::parser::{parse_with_options, ParseOptions};
+use text_to_polly_ssml::ssml_constants::PhonemeAlphabet;
+
+const PLS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<lexicon version="1.0"
+  xmlns="http://www.w3.org/2005/01/pronunciation-lexicon"
+  alphabet="ipa" xml:lang="en-US">
+  <lexeme>
+    <grapheme>pecan</grapheme>
+    <grapheme>Pecan</grapheme>
+    <phoneme>pɪˈkɑːn</phoneme>
+  </lexeme>
+  <lexeme>
+    <grapheme>W3C</grapheme>
+    <alias>World Wide Web Consortium</alias>
+  </lexeme>
+  <lexeme>
+    <grapheme>W3C &amp; friends</grapheme>
+    <phoneme alphabet="x-sampa">friends</phoneme>
+    <alias prefer="true">the consortium</alias>
+  </lexeme>
+</lexicon>"#;
+
+#[test]
+fn test_loading_pls() {
+  let lexicon = Lexicon::from_pls(PLS).unwrap();
+  assert_eq!(lexicon.alphabet, PhonemeAlphabet::Ipa);
+  assert_eq!(lexicon.lang, Some("en-US".to_owned()));
+  assert_eq!(lexicon.lexemes.len(), 3);
+  assert_eq!(lexicon.lookup("Pecan"), Some(&Pronunciation::Phoneme {
+    alphabet: PhonemeAlphabet::Ipa,
+    ph: "pɪˈkɑːn".to_owned(),
+  }));
+  assert_eq!(lexicon.lookup("W3C & friends"), Some(&Pronunciation::Alias("the consortium".to_owned())));
+  assert_eq!(lexicon.lookup("PECAN"), None);
+
+  assert!(Lexicon::from_pls("<lexicon><lexeme><grapheme>a</grapheme></lexeme></lexicon>").is_err());
+  assert!(Lexicon::from_pls(r#"<lexicon alphabet="klingon"></lexicon>"#).is_err());
+}
+
+#[test]
+fn test_parsing_with_lexicon() {
+  let options = ParseOptions { lexicon: Some(Lexicon::from_pls(PLS).unwrap()), ..ParseOptions::default() };
+  let output = parse_with_options(
+    "A pecan pie from the W3C, pecans, W3C & friends, ${phoneme|alphabet=ipa|ph=a}pecan${/phoneme} ${sub|alias=b}W3C${/sub}",
+    &options,
+  ).unwrap();
+  assert!(output.ssml.ends_with(concat!(
+    r#">A <phoneme alphabet="ipa" ph="pɪˈkɑːn">pecan</phoneme> pie from the "#,
+    r#"<sub alias="World Wide Web Consortium">W3C</sub>, pecans, "#,
+    r#"<sub alias="the consortium">W3C &amp; friends</sub>, "#,
+    r#"<phoneme alphabet="ipa" ph="a">pecan</phoneme> <sub alias="b">W3C</sub></speak>"#,
+  )), "{}", output.ssml);
+}
+
+#[test]
+fn test_collecting_lexicon() {
+  use text_to_polly_ssml::lexicon::collect_lexicon;
+
+  let collected = collect_lexicon(&[
+    ("intro.txt", "${s}${sub|alias=World Wide Web Consortium}W3C${/sub} likes ${phoneme|alphabet=x-sampa|ph=pI\"kA:n}pecan${/phoneme} pie.${/s}"),
+    ("outro.txt", "${sub|alias=World Wide Web Consortium}W3C${/sub} & ${sub|alias=the consortium}W3C${/sub}"),
+  ], Some("en-US")).unwrap();
+
+  assert_eq!(collected.lexicon.alphabet, PhonemeAlphabet::XSampa);
+  assert_eq!(collected.lexicon.lexemes.len(), 2);
+  assert_eq!(collected.conflicts.len(), 1);
+  assert_eq!(collected.conflicts[0].message, concat!(
+    r#""W3C" in outro.txt is said as the alias "the consortium", but as the alias "#,
+    r#""World Wide Web Consortium" in intro.txt. Keeping the alias "World Wide Web Consortium"."#,
+  ));
+  assert_eq!(collected.conflicts[0].span.map(|span| span.start), Some(51));
+
+  let pls = collected.lexicon.to_pls().unwrap();
+  assert!(pls.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?><lexicon version="1.0""#));
+  assert!(pls.contains(r#"alphabet="x-sampa" xml:lang="en-US">"#));
+  assert!(pls.contains(r#"<lexeme><grapheme>pecan</grapheme><phoneme>pI&quot;kA:n</phoneme></lexeme>"#));
+  assert_eq!(Lexicon::from_pls(&pls).unwrap(), collected.lexicon);
+}
@@ -56,3 +56,48 @@ Now some more complex auto breaths. <amazon:auto-breaths volume="x-loud" frequen
 We can even do manual breaths! <amazon:breath volume="default" duration="default"/>
 Or an even more complex breath! <amazon:breath volume="x-loud" duration="x-long"/></speak>"#);
 }
+
+#[test]
+fn test_long_breaks() {
+  use text_to_polly_ssml::parser::{parse_with_options, ParseOptions};
+  use text_to_polly_ssml::ssml_constants::BreakTime;
+
+  let result = text_to_polly_ssml::parse_string(r#"a ${break|time=1.5s} b ${break|time=30s}"#.to_owned());
+  assert!(result.unwrap().ends_with(r#">a <break time="1500ms"/> b <break time="10s"/></speak>"#));
+
+  let options = ParseOptions { strict: true, max_break_time: BreakTime::new(2, true), ..ParseOptions::default() };
+  assert!(parse_with_options("${break|time=2s}", &options).is_ok());
+  assert!(parse_with_options("${break|time=2001ms}", &options).is_err());
+
+  let output = parse_with_options("ab ${break|time=11s}", &ParseOptions::default()).unwrap();
+  assert_eq!(output.diagnostics.len(), 1);
+  assert_eq!(output.diagnostics[0].message, "A break of 11s is longer than the limit of 10s.");
+  assert_eq!(output.diagnostics[0].span.map(|span| (span.start, span.end)), Some((3, 20)));
+}
+
+#[test]
+fn test_emphasis() {
+  use text_to_polly_ssml::dialect::PollyDialect;
+  use text_to_polly_ssml::parser::{parse_with_dialect, ParseOptions};
+  use text_to_polly_ssml::ssml_constants::PollyEngine;
+
+  let text = concat!(
+    "${emphasis|level=strong}a${/emphasis} ${emphasis}b${/emphasis} ${emphasis|level=reduced}c${/emphasis} ",
+    "${emphasis|level=none}d${/emphasis} ${emphasis|level=loud}e${/emphasis}",
+  );
+  let result = text_to_polly_ssml::parse_string(text.to_owned());
+  assert!(result.unwrap().ends_with(concat!(
+    r#"><emphasis level="strong">a</emphasis> <emphasis>b</emphasis> <emphasis level="reduced">c</emphasis> "#,
+    r#"<emphasis level="none">d</emphasis> <emphasis>e</emphasis></speak>"#,
+  )));
+
+  let output = parse_with_dialect(text, &ParseOptions::default(), PollyDialect::new(PollyEngine::Neural)).unwrap();
+  assert!(output.ssml.ends_with(concat!(
+    r#"><prosody volume="+6dB" rate="slow">a</prosody> <prosody volume="+3dB">b</prosody> "#,
+    r#"<prosody volume="-6dB" rate="fast">c</prosody> d <prosody volume="+3dB">e</prosody></speak>"#,
+  )));
+  assert_eq!(output.diagnostics.len(), 4);
+  assert_eq!(output.diagnostics[0].message,
+    "Polly's neural voices don't support <emphasis>, it was written as <prosody> instead.");
+  assert_eq!(output.diagnostics[0].span.map(|span| (span.start, span.end)), Some((0, 24)));
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "markdown")]
+
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::dialect::{GoogleDialect, PollyDialect};
+use text_to_polly_ssml::markdown::{markdown_to_ssml, MarkdownOptions};
+use text_to_polly_ssml::parser::ParseOptions;
+
+#[test]
+fn test_markdown_to_ssml() {
+  let markdown = concat!(
+    "## Install\n",
+    "\n",
+    "Run `cargo ${build}` from [the docs](https://example.com), then **restart**.\n",
+    "![diagram](diagram.png)\n",
+    "\n",
+    "* First\n",
+    "* Second\n",
+    "  1. Nested\n",
+    "\n",
+    "```\n",
+    "fn main() {}\n",
+    "```\n",
+    "\n",
+    "---\n",
+    "${sub|alias=World Wide Web Consortium}W3C${/sub} says *hi*  \n",
+    "there\n",
+  );
+  let output = markdown_to_ssml(markdown, &MarkdownOptions::default(), &ParseOptions::default(),
+    GoogleDialect::default()).unwrap();
+
+  assert!(output.ssml.ends_with(concat!(
+    r#"<speak><break strength="strong"/><p><prosody rate="slow" volume="loud">Install</prosody></p>"#,
+    r#"<p>Run cargo ${build} from the docs, then <emphasis level="strong">restart</emphasis>. </p>"#,
+    r#"<s>First</s><break strength="medium"/><s>Second</s><break strength="medium"/>"#,
+    r#"<s>Nested</s><break strength="medium"/><break strength="x-strong"/>"#,
+    r#"<p><sub alias="World Wide Web Consortium">W3C</sub> says <emphasis level="moderate">hi</emphasis>"#,
+    r#"<break strength="weak"/>there</p></speak>"#,
+  )));
+  assert!(output.diagnostics.is_empty());
+}
+
+#[test]
+fn test_markdown_options() {
+  let options = MarkdownOptions { spell_out_code: true, emphasis_as_prosody: true };
+  let markdown = "Call **`SSML`** ${break|time=30s}";
+  let output = markdown_to_ssml(markdown, &options, &ParseOptions::default(), PollyDialect::default()).unwrap();
+
+  assert!(output.ssml.ends_with(concat!(
+    r#"><p>Call <prosody volume="+6dB" rate="slow"><say-as interpret-as="characters">SSML</say-as></prosody> "#,
+    r#"<break time="10s"/></p></speak>"#,
+  )));
+  assert_eq!(output.diagnostics.len(), 1);
+  assert_eq!(output.diagnostics[0].span.map(|span| &markdown[span.start..span.end]), Some("${break|time=30s}"));
+}
+
+#[test]
+fn test_markdown_inside_markup() {
+  let markdown = "${sub|alias=A *big* deal}ABD${/sub} and ${phoneme|alphabet=ipa|ph=pɪˈkɑn}*pecan*${/phoneme}";
+  let output = markdown_to_ssml(markdown, &MarkdownOptions::default(), &ParseOptions::default(),
+    GoogleDialect::default()).unwrap();
+
+  assert!(output.ssml.ends_with(concat!(
+    r#"<p><sub alias="A *big* deal">ABD</sub> and "#,
+    r#"<phoneme alphabet="ipa" ph="pɪˈkɑn"><emphasis level="moderate">pecan</emphasis></phoneme></p></speak>"#,
+  )));
+  assert!(output.diagnostics.is_empty());
+}
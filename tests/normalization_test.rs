@@ -0,0 +1,51 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::document::Document;
+use text_to_polly_ssml::normalization::{normalize, NormalizationOptions, Pattern};
+use text_to_polly_ssml::parser::{parse_with_options, ParseOptions};
+
+fn normalized(markup: &str, options: &NormalizationOptions) -> String {
+  let mut document = Document::from_markup(markup).unwrap();
+  normalize(&mut document, options);
+  let ssml = document.render().unwrap();
+  let body_start = ssml.find("\">").unwrap() + 2;
+  ssml[body_start..ssml.len() - "</speak>".len()].to_owned()
+}
+
+#[test]
+fn test_normalizing_patterns() {
+  let options = NormalizationOptions::default();
+  assert_eq!(
+    normalized("Add 3/4 cup, drive 10km or 5.5km/h, call (555) 123-4567 on 03/05/2024 about order #1234 for 007.", &options),
+    concat!(
+      r#"Add <say-as interpret-as="fraction">3/4</say-as> cup, "#,
+      r#"drive <say-as interpret-as="unit">10km</say-as> or <say-as interpret-as="unit">5.5km/h</say-as>, "#,
+      r#"call <say-as interpret-as="telephone">(555) 123-4567</say-as> "#,
+      r#"on <say-as interpret-as="date" format="mdy">03/05/2024</say-as> "#,
+      r#"about order #<say-as interpret-as="digits">1234</say-as> for <say-as interpret-as="digits">007</say-as>."#,
+    ),
+  );
+  assert_eq!(normalized("Costs £2bn, not 13/45/2024 or a2024-03-05.", &options),
+    r#"Costs <sub alias="2 billion pounds">£2bn</sub>, not 13/45/2024 or a2024-03-05."#);
+  assert_eq!(normalized("Paid $1,050 of 1,000,000, at 10:05, for agent: 007, 1/2.", &options),
+    r#"Paid $1,050 of 1,000,000, at 10:05, for agent: <say-as interpret-as="digits">007</say-as>, <say-as interpret-as="fraction">1/2</say-as>."#);
+  assert_eq!(normalized("${say-as|interpret-as=cardinal}3/4${/say-as}", &options),
+    r#"<say-as interpret-as="cardinal">3/4</say-as>"#);
+}
+
+#[test]
+fn test_normalizing_options() {
+  let options = NormalizationOptions { locale: "en-GB".to_owned(), patterns: vec![Pattern::Date] };
+  assert_eq!(normalized("03/05/2024 and 3/4", &options),
+    r#"<say-as interpret-as="date" format="dmy">03/05/2024</say-as> and 3/4"#);
+
+  let options = NormalizationOptions { locale: "fr-FR".to_owned(), ..NormalizationOptions::default() };
+  assert_eq!(normalized("Levé 1,5M€ et $2M le 03/05/2024, à 10km.", &options), concat!(
+    r#"Levé 1,5M€ et $2M le <say-as interpret-as="date" format="dmy">03/05/2024</say-as>, "#,
+    r#"à <say-as interpret-as="unit">10km</say-as>."#,
+  ));
+
+  let options = ParseOptions { normalization: Some(NormalizationOptions::default()), ..ParseOptions::default() };
+  let output = parse_with_options("It's 10km away.", &options).unwrap();
+  assert!(output.ssml.ends_with(r#">It&apos;s <say-as interpret-as="unit">10km</say-as> away.</speak>"#));
+}
@@ -0,0 +1,37 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::phonetics::{convert, ipa_to_x_sampa, validate_ph, x_sampa_to_ipa};
+use text_to_polly_ssml::ssml_constants::PhonemeAlphabet;
+
+#[test]
+fn test_conversion_round_trips() {
+  for ipa in &["ˈdʒæk", "θɪŋk", "ˌɛkspləˈneɪʃən", "ɹɛd", "bɝd", "tʰɒp", "kʰæt̚", "d͡ʒʌmp"] {
+    let x_sampa = ipa_to_x_sampa(ipa).unwrap();
+    assert_eq!(x_sampa_to_ipa(&x_sampa).unwrap(), *ipa);
+  }
+  assert_eq!(ipa_to_x_sampa("ˌɛkspləˈneɪʃən").unwrap(), "%Ekspl@\"neIS@n");
+  assert_eq!(ipa_to_x_sampa("ɹɛd bɝd").unwrap(), "r\\Ed b3`d");
+  assert_eq!(x_sampa_to_ipa("r\\`Aj").unwrap(), "ɻɑj");
+  assert_eq!(ipa_to_x_sampa("d͡ʒʌmp").unwrap(), "d_ZVmp");
+  assert_eq!(convert("tʰɒp", PhonemeAlphabet::Ipa, PhonemeAlphabet::Ipa).unwrap(), "tʰɒp");
+  assert!(ipa_to_x_sampa("pɪ★kɑːn").is_err());
+}
+
+#[test]
+fn test_validating_ph() {
+  assert!(validate_ph("pɪˈkɑn", PhonemeAlphabet::Ipa, Some("en-US")).is_empty());
+  assert!(validate_ph("pI\"kAn", PhonemeAlphabet::XSampa, Some("en-us")).is_empty());
+
+  let diagnostics = validate_ph("ʁøʒ", PhonemeAlphabet::Ipa, Some("en-GB"));
+  let messages = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect::<Vec<&str>>();
+  assert_eq!(messages, vec![
+    "\"ʁ\" is not a phoneme Polly supports for en-GB.",
+    "\"ø\" is not a phoneme Polly supports for en-GB.",
+  ]);
+  assert_eq!(diagnostics[1].span.map(|span| (span.start, span.end)), Some((2, 4)));
+  assert!(validate_ph("ʁøʒ", PhonemeAlphabet::Ipa, Some("fr-FR")).is_empty());
+
+  let result = text_to_polly_ssml::validate_ssml(r#"<speak><phoneme alphabet="x-sampa" ph="pɪkɑn">pecan</phoneme></speak>"#);
+  assert_eq!(result.len(), 2);
+  assert_eq!(result[0].message, "Invalid \"ph\" on <phoneme>: \"ɪ\" is not a valid x-sampa symbol.");
+}
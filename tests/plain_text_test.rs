@@ -0,0 +1,39 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::plain_text::{markup_to_plain_text, ssml_to_plain_text, PlainTextOptions};
+
+#[test]
+fn test_markup_to_plain_text() {
+  let result = text_to_polly_ssml::to_plain_text(r#"Hello ${break|time=4s}${prosody|volume=+6dB}loud${/prosody}, ${sub|alias=mercury}hg${/sub}${amazon:breath} and ${say-as|interpret-as=spell-out}abc${/say-as}. Escaped: $\{p}"#.to_owned());
+  assert!(result.is_ok());
+  assert_eq!(result.unwrap(), "Hello loud, mercury and abc. Escaped: ${p}");
+}
+
+#[test]
+fn test_markup_to_plain_text_spell_out() {
+  let options = PlainTextOptions { expand_spell_out: true };
+  let result = markup_to_plain_text(r#"Call ${say-as|interpret-as=characters} NASA ${/say-as} now"#, &options);
+  assert!(result.is_ok());
+  assert_eq!(result.unwrap(), "Call  N A S A  now");
+}
+
+#[test]
+fn test_generated_ssml_to_plain_text() {
+  let markup = r#"${p}I'm ${sub|alias=mercury}hg${/sub}.${/p}${break} ${say-as|interpret-as=spell-out}ab${/say-as}"#;
+  let ssml = text_to_polly_ssml::parse_string(markup.to_owned()).unwrap();
+  let options = PlainTextOptions { expand_spell_out: true };
+  let result = ssml_to_plain_text(&ssml, &options);
+  assert!(result.is_ok());
+  assert_eq!(result.unwrap(), markup_to_plain_text(markup, &options).unwrap());
+  assert_eq!(ssml_to_plain_text(&ssml, &options).unwrap(), "I'm mercury. a b");
+}
+
+#[test]
+fn test_plain_text_keeps_words_apart() {
+  let markup = "${p}Hello.${/p}${p}World${/p} one${break}two${s}three${/s}${s}four${/s}";
+  let options = PlainTextOptions::default();
+  assert_eq!(markup_to_plain_text(markup, &options).unwrap(), "Hello.\nWorld one two three four");
+
+  let ssml = text_to_polly_ssml::parse_string(markup.to_owned()).unwrap();
+  assert_eq!(ssml_to_plain_text(&ssml, &options).unwrap(), "Hello.\nWorld one two three four");
+}
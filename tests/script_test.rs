@@ -0,0 +1,75 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::ParseOptions;
+use text_to_polly_ssml::script::{parse_script, ScriptConfig, Speaker};
+use text_to_polly_ssml::ssml_constants::{PollyEngine, ProsodyRate};
+
+fn config() -> ScriptConfig {
+  let mut config = ScriptConfig::default();
+  config.insert("ALICE", Speaker::new("Joanna"));
+  config.insert("BOB", Speaker { rate: Some(ProsodyRate::Slow), pitch: Some("-10%".to_owned()), ..Speaker::new("Matthew") });
+  config.insert("Dr Smith", Speaker::new("Brian"));
+  config
+}
+
+#[test]
+fn test_parse_script() {
+  let script = concat!(
+    "ALICE: Hello ${break|time=300ms} BOB: Hi.\n",
+    "How are you?\n",
+    "\n",
+    "DR SMITH: ${sub|alias=Note: this}NB${/sub} ${break|time=20s}\n",
+    "ALICE:\n",
+    "BOB: ${emphasis}Fine!${/emphasis}",
+  );
+  let lines = parse_script(script, &config(), &ParseOptions::default()).unwrap();
+
+  let speakers = lines.iter().map(|line| (line.speaker.as_str(), line.voice.as_str())).collect::<Vec<_>>();
+  assert_eq!(speakers, vec![("ALICE", "Joanna"), ("BOB", "Matthew"), ("DR SMITH", "Brian"), ("BOB", "Matthew")]);
+  assert!(lines[0].ssml.ends_with(r#">Hello <break time="300ms"/></speak>"#));
+  assert!(lines[1].ssml.ends_with("><prosody rate=\"slow\" pitch=\"-10%\">Hi.\nHow are you?</prosody></speak>"));
+  assert!(lines[2].ssml.ends_with(r#"><sub alias="Note: this">NB</sub> <break time="10s"/></speak>"#));
+  assert!(lines[3].ssml.ends_with(r#"><prosody rate="slow" pitch="-10%"><emphasis>Fine!</emphasis></prosody></speak>"#));
+
+  assert_eq!(lines[2].diagnostics.len(), 1);
+  assert_eq!(lines[2].diagnostics[0].span.map(|span| &script[span.start..span.end]), Some("${break|time=20s}"));
+}
+
+#[test]
+fn test_script_errors() {
+  let options = ParseOptions::default();
+  assert!(parse_script("Hello ALICE: hi", &config(), &options).is_err());
+  assert!(parse_script("CAROL: hey", &config(), &options).is_err());
+  assert!(parse_script("", &config(), &options).unwrap().is_empty());
+
+  let lines = parse_script("ALICE: hi CAROL: hey", &config(), &options).unwrap();
+  assert_eq!(lines.len(), 1);
+  assert!(lines[0].ssml.ends_with(">hi CAROL: hey</speak>"));
+  let lines = parse_script("ALICE: Read the FAQ: it helps.", &config(), &options).unwrap();
+  assert_eq!(lines.len(), 1);
+  assert!(lines[0].ssml.ends_with(">Read the FAQ: it helps.</speak>"));
+  let lines = parse_script("ALICE: Say HI BOB: hey", &config(), &options).unwrap();
+  assert_eq!(lines.iter().map(|line| line.speaker.as_str()).collect::<Vec<&str>>(), vec!["ALICE", "BOB"]);
+  assert!(lines[0].ssml.ends_with(">Say HI</speak>"));
+
+  let mut config = config();
+  config.engine = PollyEngine::Neural;
+  let lines = parse_script("ALICE: ${emphasis|level=strong}No${/emphasis}", &config, &options).unwrap();
+  assert!(lines[0].ssml.ends_with(r#"><prosody volume="+6dB" rate="slow">No</prosody></speak>"#));
+  assert_eq!(lines[0].diagnostics.len(), 1);
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_script_config_from_toml() {
+  let config = ScriptConfig::from_toml(r#"
+    engine = "neural"
+
+    [speakers.ALICE]
+    voice = "Joanna"
+    volume = "soft"
+  "#).unwrap();
+  assert_eq!(config.engine, PollyEngine::Neural);
+  assert_eq!(config.lookup("alice").map(|speaker| speaker.voice.as_str()), Some("Joanna"));
+  assert_eq!(config.speakers["ALICE"].volume.as_deref(), Some("soft"));
+}
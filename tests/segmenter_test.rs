@@ -0,0 +1,33 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{parse_with_options, ParseOptions};
+
+fn segmented(text: &str) -> String {
+  let options = ParseOptions { segment: true, ..ParseOptions::default() };
+  let ssml = parse_with_options(text, &options).unwrap().ssml;
+  let body_start = ssml.find("\">").unwrap() + 2;
+  ssml[body_start..ssml.len() - "</speak>".len()].to_owned()
+}
+
+#[test]
+fn test_segmenting_plain_text() {
+  assert_eq!(
+    segmented("Dr. Smith paid $3.50 for it. Was it worth it? \"Yes!\" she said.\n\n  Next paragraph, e.g. this one. J. R. R. Tolkien wrote it...\n"),
+    concat!(
+      "<p><s>Dr. Smith paid $3.50 for it.</s> <s>Was it worth it?</s> <s>&quot;Yes!&quot; she said.</s></p>\n\n  ",
+      "<p><s>Next paragraph, e.g. this one.</s> <s>J. R. R. Tolkien wrote it...</s></p>\n",
+    ),
+  );
+}
+
+#[test]
+fn test_segmenting_around_markup() {
+  assert_eq!(
+    segmented("Hi. ${prosody|rate=slow}Slow. Still slow.${/prosody} Back.${break} Done.\n\n${p}Mine. All mine.${/p} ${s}Me too.${/s} After."),
+    concat!(
+      r#"<p><s>Hi.</s> <s><prosody rate="slow">Slow. Still slow.</prosody></s> <s>Back.<break/></s> <s>Done.</s></p>"#,
+      "\n\n<p>Mine. All mine.</p> <s>Me too.</s> <p><s>After.</s></p>",
+    ),
+  );
+  assert_eq!(segmented("${p}Untouched.${/p}"), "<p>Untouched.</p>");
+}
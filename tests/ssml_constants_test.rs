@@ -0,0 +1,80 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::ssml_constants::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn test_variants_are_unique() {
+  assert_eq!(ProsodyRate::variants().count(), 5);
+  assert_eq!(ProsodyRate::variants().collect::<HashSet<_>>().len(), 5);
+  assert_eq!(PossibleOpenTags::variants().count(), 16);
+  assert_eq!(BreathVolumes::variants().map(|volume| volume.to_string()).collect::<Vec<_>>(),
+    vec!["default", "x-soft", "soft", "medium", "loud", "x-loud"]);
+}
+
+#[test]
+fn test_break_time_equality() {
+  assert_eq!(BreakTime::new(10, true), BreakTime::new(10, true));
+  assert_ne!(BreakTime::new(10, true), BreakTime::new(10, false));
+  assert_eq!("10s".parse::<BreakTime>(), Ok(BreakTime::new(10, true)));
+  assert_eq!(BreakTime::new(2, true), BreakTime::new(2000, false));
+}
+
+#[test]
+fn test_break_time_parsing() {
+  assert_eq!("1.5s".parse::<BreakTime>(), Ok(BreakTime::from_milliseconds(1500)));
+  assert_eq!("250.4ms".parse::<BreakTime>(), Ok(BreakTime::from_milliseconds(250)));
+  assert_eq!(".5s".parse::<BreakTime>().map(|time| time.to_string()), Ok("500ms".to_owned()));
+  assert_eq!("3000ms".parse::<BreakTime>().map(|time| time.to_string()), Ok("3s".to_owned()));
+  for invalid in &["s", "ms", "1.2.3s", "-1s", "+1s", "1e3ms", "infs", "10", "99999999999s"] {
+    assert!(invalid.parse::<BreakTime>().is_err(), "{} should not parse", invalid);
+  }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_uses_ssml_strings() {
+  let as_json = serde_json::to_string(&vec![ProsodyRate::XFast, ProsodyRate::Slow]).unwrap();
+  assert_eq!(as_json, r#"["x-fast","slow"]"#);
+  let back: Vec<ProsodyRate> = serde_json::from_str(&as_json).unwrap();
+  assert_eq!(back, vec![ProsodyRate::XFast, ProsodyRate::Slow]);
+
+  assert_eq!(serde_json::to_string(&WordRole::Verb).unwrap(), r#""amazon:VB""#);
+  assert_eq!(serde_json::to_string(&BreakTime::new(500, false)).unwrap(), r#""500ms""#);
+  assert_eq!(serde_json::from_str::<BreakTime>(r#""4s""#).unwrap(), BreakTime::new(4, true));
+  assert!(serde_json::from_str::<ProsodyRate>(r#""warp-speed""#).is_err());
+}
+
+macro_rules! assert_round_trips {
+  ($($kind:ident),*) => {
+    $(
+      for value in $kind::variants() {
+        assert_eq!(value.to_string().parse::<$kind>(), Ok(value), "{} didn't round trip", value);
+      }
+    )*
+  };
+}
+
+#[test]
+fn test_display_round_trips() {
+  assert_round_trips!(BreakStrength, PhonemeAlphabet, ProsodyRate, WordRole, EmphasisLevel,
+    PollyEngine, AmazonEffect, BreathVolumes, BreathDuration, AutoBreathFrequency, PhonationVolume, PossibleClosingTags,
+    PossibleOpenTags);
+
+  for milliseconds in (0..25_000).step_by(7) {
+    let time = BreakTime::from_milliseconds(milliseconds);
+    assert_eq!(time.to_string().parse::<BreakTime>(), Ok(time));
+  }
+}
+
+#[test]
+fn test_break_strength_durations() {
+  let durations = BreakStrength::variants()
+    .map(|strength| strength.nominal_duration().as_milliseconds())
+    .collect::<Vec<u32>>();
+  assert_eq!(durations, vec![0, 0, 250, 250, 500, 1000]);
+
+  let result = text_to_polly_ssml::parse_string("${break|strength=none}".to_owned());
+  assert!(result.unwrap().ends_with(r#"><break strength="none"/></speak>"#));
+}
@@ -0,0 +1,66 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{ParseOptions, StreamingParser, MAX_TAG_LENGTH};
+
+const MARKUP: &str = r#"I'm going to stop talking for a bit. ${break|strength=strong|time=4s}
+How about a phoneme? ${phoneme|alphabet=ipa|ph=pɪˈkɑːn} pecan ${/phoneme}
+Now even more ${prosody|volume=+6db|rate=x-fast|pitch=+4%} coffee ${/prosody} costs $5.
+Escaped: $\{p} and a breath ${amazon:breath|volume=x-loud|duration=x-long}"#;
+
+fn stream_in_chunks(chunk_size: usize) -> String {
+  let mut parser = StreamingParser::new(Vec::new()).unwrap();
+  let characters = MARKUP.chars().collect::<Vec<char>>();
+  for chunk in characters.chunks(chunk_size) {
+    assert!(parser.feed(&chunk.iter().collect::<String>()).is_ok());
+  }
+  String::from_utf8(parser.finish().unwrap()).unwrap()
+}
+
+#[test]
+fn test_streaming_matches_batch_parsing() {
+  let expected = text_to_polly_ssml::parse_string(MARKUP.to_owned()).unwrap();
+  for chunk_size in &[1, 2, 3, 7, 64, MARKUP.len()] {
+    assert_eq!(stream_in_chunks(*chunk_size), expected);
+  }
+}
+
+#[test]
+fn test_streaming_holds_incomplete_tags() {
+  let mut parser = StreamingParser::new(Vec::new()).unwrap();
+  assert!(parser.feed("${s").unwrap().is_empty());
+  assert!(parser.feed("}").unwrap()[0].start_tag.is_some());
+  assert_eq!(parser.feed("done$").unwrap()[0].data, Some("done".to_owned()));
+  assert!(parser.feed("{/s}").unwrap()[0].end_tag.is_some());
+  let ssml = String::from_utf8(parser.finish().unwrap()).unwrap();
+  assert!(ssml.ends_with("<s>done</s></speak>"));
+}
+
+#[test]
+fn test_streaming_caps_unterminated_tags() {
+  let mut parser = StreamingParser::new(Vec::new()).unwrap();
+  assert!(parser.feed("${prosody|volume=").unwrap().is_empty());
+  let text = "a".repeat(MAX_TAG_LENGTH);
+  let items = parser.feed(&text).unwrap();
+  assert_eq!(items.len(), 1);
+  assert_eq!(items[0].data.as_ref().map(|data| data.len()), Some(MAX_TAG_LENGTH + 17));
+  assert_eq!(parser.diagnostics().len(), 1);
+  assert_eq!(parser.feed("${s}").unwrap().len(), 1);
+  let ssml = String::from_utf8(parser.finish().unwrap()).unwrap();
+  assert!(ssml.ends_with("aaa<s></speak>"));
+}
+
+#[test]
+fn test_streaming_limits_breaks() {
+  let text = "a ${break|time=20s} b";
+  let mut parser = StreamingParser::new(Vec::new()).unwrap();
+  assert_eq!(parser.feed(text).unwrap().len(), 3);
+  assert_eq!(parser.diagnostics()[0].message, "A break of 20s is longer than the limit of 10s.");
+  let ssml = String::from_utf8(parser.finish().unwrap()).unwrap();
+  assert_eq!(ssml, text_to_polly_ssml::parse_string(text.to_owned()).unwrap());
+  assert!(ssml.ends_with(r#">a <break time="10s"/> b</speak>"#));
+
+  let options = ParseOptions { strict: true, ..ParseOptions::default() };
+  let mut parser = StreamingParser::new(Vec::new()).unwrap().with_options(options);
+  assert_eq!(parser.feed("a ${break|time=").unwrap().len(), 1);
+  assert!(parser.feed("20s} b").is_err());
+}
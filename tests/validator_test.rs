@@ -0,0 +1,61 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::diagnostics::Severity;
+use text_to_polly_ssml::validator::validate_ssml;
+
+#[test]
+fn test_validate_generated_ssml() {
+  let ssml = text_to_polly_ssml::parse_string(r#"${p}${s}Hello ${break|time=4s}${prosody|volume=+6dB|rate=x-slow}loud${/prosody}, ${sub|alias=mercury}hg${/sub}${amazon:breath|volume=x-loud} and ${phoneme|alphabet=ipa|ph=pɪˈkɑn}pecan${/phoneme}.${/s}${/p}"#.to_owned()).unwrap();
+  let result = text_to_polly_ssml::validate_ssml(&ssml);
+  assert!(result.is_empty(), "{:?}", result);
+}
+
+#[test]
+fn test_validate_structure() {
+  let result = validate_ssml(r#"<p>hey</p>"#);
+  assert_eq!(result.len(), 1);
+  assert_eq!(result[0].message, "The root tag must be <speak>, not <p>.");
+
+  let result = validate_ssml(r#"<speak><phoneme alphabet="ipa" ph="a"><s>a</s></phoneme><break>nope</break></speak>"#);
+  let messages = result.iter().map(|diagnostic| diagnostic.message.as_str()).collect::<Vec<&str>>();
+  assert_eq!(messages, vec![
+    "<phoneme> can only contain text, not <s>.",
+    "<break> can't contain anything.",
+  ]);
+  assert_eq!(result[0].span.unwrap().start, 38);
+
+  let result = validate_ssml(r#"<speak><s>unclosed</speak>"#);
+  assert_eq!(result.len(), 1);
+  assert!(result[0].message.starts_with("Invalid XML"));
+}
+
+#[test]
+fn test_validate_attributes() {
+  let result = validate_ssml(r#"<speak><say-as>a</say-as><break time="forever" strength="x-strong"/><prosody volume="6dB" pitch="+5%" color="red">a</prosody><video>a</video></speak>"#);
+  let found = result.iter()
+    .map(|diagnostic| (diagnostic.severity, diagnostic.message.as_str()))
+    .collect::<Vec<(Severity, &str)>>();
+  assert_eq!(found, vec![
+    (Severity::Error, "<say-as> is missing the required attribute \"interpret-as\"."),
+    (Severity::Error, "\"forever\" is not a valid value for \"time\" on <break>."),
+    (Severity::Warning, "<prosody> has an attribute Polly doesn't know about: \"color\"."),
+    (Severity::Error, "\"6dB\" is not a valid value for \"volume\" on <prosody>."),
+    (Severity::Error, "<video> is not supported by Polly."),
+  ]);
+}
+
+#[test]
+fn test_validate_phonemes_for_language() {
+  let result = validate_ssml(concat!(
+    r#"<speak xml:lang="en-US"><phoneme alphabet="ipa" ph="pɪˈkɑːn">pecan</phoneme>"#,
+    r#"<lang xml:lang="en-GB"><phoneme alphabet="ipa" ph="pɪˈkɑːn">pecan</phoneme></lang>"#,
+    r#"<phoneme alphabet="ipa" ph="kɑːn">pecan</phoneme></speak>"#,
+  ));
+  let found = result.iter().map(|diagnostic| diagnostic.message.as_str()).collect::<Vec<&str>>();
+  assert_eq!(found, vec![
+    "Invalid \"ph\" on <phoneme>: \"ː\" is not a phoneme Polly supports for en-US.",
+    "Invalid \"ph\" on <phoneme>: \"ː\" is not a phoneme Polly supports for en-US.",
+  ]);
+
+  assert!(validate_ssml(r#"<speak><phoneme alphabet="ipa" ph="pɪˈkɑːn">pecan</phoneme></speak>"#).is_empty());
+}
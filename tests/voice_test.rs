@@ -0,0 +1,59 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::dialect::{AlexaDialect, GoogleDialect};
+use text_to_polly_ssml::parser::{parse_with_dialect, parse_with_options, ParseOptions};
+use text_to_polly_ssml::ssml_constants::PollyEngine;
+use text_to_polly_ssml::voice::{split_by_voice, VoiceId};
+
+#[test]
+fn test_voice_tags() {
+  let text = "Hi. ${voice|name=Matthew}Hello!${/voice}";
+
+  let output = parse_with_dialect(text, &ParseOptions::default(), GoogleDialect::default()).unwrap();
+  assert!(output.ssml.ends_with(r#"<speak>Hi. <voice name="Matthew">Hello!</voice></speak>"#));
+  let output = parse_with_dialect(text, &ParseOptions::default(), AlexaDialect::default()).unwrap();
+  assert_eq!(output.ssml, r#"<speak>Hi. <voice name="Matthew">Hello!</voice></speak>"#);
+
+  let output = parse_with_options(text, &ParseOptions::default()).unwrap();
+  assert!(output.ssml.ends_with(">Hi. Hello!</speak>"));
+  assert_eq!(output.diagnostics[0].message, "Polly doesn't support <voice>, it was left out.");
+}
+
+#[test]
+fn test_split_by_voice() {
+  let segments = split_by_voice(
+    concat!(
+      "${voice|name=Joanna}Hi Matthew.${/voice} ${voice|name=Matthew}Hey! ${voice}${break|time=1s}${/voice}",
+      "${s}How ${voice|name=Ivy}are${/voice} you?${/s}${/voice} ${voice|name=Joanna}${break|time=2s}${/voice}",
+      "Bye.",
+    ),
+    &ParseOptions::default(),
+    &VoiceId::new("Kendra"),
+    PollyEngine::Neural,
+  ).unwrap();
+
+  let voices = segments.iter().map(|(voice, _)| voice.as_str()).collect::<Vec<&str>>();
+  assert_eq!(voices, vec!["Joanna", "Matthew", "Ivy", "Matthew", "Joanna", "Kendra"]);
+  assert!(segments[0].1.ssml.ends_with(">Hi Matthew. </speak>"));
+  assert!(segments[1].1.ssml.ends_with(r#">Hey! <break time="1s"/><s>How </s></speak>"#));
+  assert!(segments[2].1.ssml.ends_with("><s>are</s></speak>"));
+  assert!(segments[3].1.ssml.ends_with("><s> you?</s> </speak>"));
+  assert!(segments[4].1.ssml.ends_with(r#"><break time="2s"/></speak>"#));
+  assert!(segments[5].1.ssml.ends_with(">Bye.</speak>"));
+}
+
+#[test]
+fn test_split_by_voice_joins_runs() {
+  let segments = split_by_voice(
+    "${prosody|rate=slow}Hi ${voice|name=Ivy}${mark|name=here}${/mark}${/voice}there${/prosody}${break|time=30s}",
+    &ParseOptions::default(),
+    &VoiceId::new("Kendra"),
+    PollyEngine::Standard,
+  ).unwrap();
+
+  assert_eq!(segments.len(), 1);
+  assert_eq!(segments[0].0, VoiceId::new("Kendra"));
+  assert!(segments[0].1.ssml.ends_with(
+    r#"><prosody rate="slow">Hi <mark name="here"></mark>there</prosody><break time="10s"/></speak>"#));
+  assert_eq!(segments[0].1.diagnostics.len(), 1);
+}
@@ -0,0 +1,33 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::xml_writer::XmlWriter;
+
+use std::fs::{self, File};
+use std::path::Path;
+
+#[test]
+fn test_streaming_into_a_file() {
+  let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("streaming-test-{}.xml", std::process::id()));
+  {
+    let file = File::create(&path).unwrap();
+    let mut xml_writer = XmlWriter::from_writer(file).unwrap();
+    assert!(xml_writer.start_ssml_speak(None, None).is_ok());
+    assert!(xml_writer.start_ssml_sub("mercury".to_owned()).is_ok());
+    assert!(xml_writer.write_text("hg").is_ok());
+    assert!(xml_writer.end_ssml_sub().is_ok());
+    assert!(xml_writer.end_ssml_speak().is_ok());
+  }
+  let written = fs::read_to_string(&path).unwrap();
+  let _ = fs::remove_file(&path);
+  assert_eq!(written, r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><sub alias="mercury">hg</sub></speak>"#);
+}
+
+#[test]
+fn test_render_matches_into_string() {
+  let mut xml_writer = XmlWriter::new().unwrap();
+  assert!(xml_writer.start_ssml_sentence().is_ok());
+  assert!(xml_writer.write_text("some words").is_ok());
+  assert!(xml_writer.end_ssml_sentence().is_ok());
+  let rendered = xml_writer.render();
+  assert_eq!(rendered, xml_writer.into_string().unwrap());
+}